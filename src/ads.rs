@@ -0,0 +1,73 @@
+// ads.rs
+// ads.rs is in charge of :
+// - reading the "Ads and data" section (off-TikTok activity and inferred ad interests)
+// - telling which advertisers and apps shared events with TikTok, which events, and when
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{count_per_month, date_field, date_info_by_timestamp, str_field, ActivityItem, DateInfo, UserTimeZone};
+
+#[derive(Serialize)]
+pub struct AdStats {
+    pub off_tiktok_events: usize,
+    pub sources: HashMap<String, usize>,
+    pub event_types: HashMap<String, usize>,
+    pub events_per_month: BTreeMap<String, usize>,
+    pub event_info: Option<DateInfo>,
+    pub ad_interests: Vec<String>,
+}
+
+pub fn ad_stats(data: &Value, tz: &UserTimeZone) -> AdStats {
+    let ads = &data["Ads and data"];
+    let events = ads["Off TikTok Activity"]["OffTikTokActivityDataList"]
+        .as_array()
+        .map(|a| a.as_slice())
+        .unwrap_or(&[]);
+
+    let mut sources = HashMap::new();
+    let mut event_types = HashMap::new();
+    let mut items = Vec::new();
+
+    for event in events {
+        let source = str_field(event, &["Source", "source"]).unwrap_or("Unknown source");
+        let event_type = str_field(event, &["Event", "event"]).unwrap_or("Unknown event");
+        *sources.entry(source.to_string()).or_insert(0) += 1;
+        *event_types.entry(event_type.to_string()).or_insert(0) += 1;
+
+        items.push(ActivityItem::new(
+            date_field(event, &["TimeStamp", "Date", "date"]).unwrap_or_default(),
+            format!("{} ({})", source, event_type),
+        ));
+    }
+
+    AdStats {
+        off_tiktok_events: events.len(),
+        sources,
+        event_types,
+        events_per_month: count_per_month(&items, tz),
+        event_info: date_info_by_timestamp(&items),
+        ad_interests: ad_interests(&ads["Ad Interests"]["AdInterestCategories"]),
+    }
+}
+
+// Interests are either a list or a single string separated by "|" (sometimes ",")
+fn ad_interests(categories: &Value) -> Vec<String> {
+    let mut interests: Vec<String> = match categories {
+        Value::Array(list) => list
+            .iter()
+            .filter_map(|category| category.as_str())
+            .map(|category| category.trim().to_string())
+            .collect(),
+        Value::String(joined) => {
+            let separator = if joined.contains('|') { '|' } else { ',' };
+            joined.split(separator).map(|category| category.trim().to_string()).collect()
+        }
+        _ => Vec::new(),
+    };
+    interests.retain(|category| !category.is_empty());
+    interests.sort();
+    interests.dedup();
+    interests
+}
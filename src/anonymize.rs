@@ -0,0 +1,205 @@
+// anonymize.rs
+// anonymize.rs is in charge of :
+// - rewriting an export without the personal information it holds, so that it can be shared for research
+// - replacing user names with pseudonyms, dropping contact details and truncating IP addresses
+// - dropping GPS coordinates and the most recent location of the account
+// The result keeps the layout of a regular export, so it gives the same statistics as the original file
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+// Replaces every message, comment and bio when it is set
+const REMOVED_TEXT: &str = "[removed]";
+
+const CHAT_PREFIX: &str = "Chat History with ";
+
+// The profile of older and newer exports
+const PROFILE_INFO: &str = "/Profile/Profile Info";
+const PROFILE_MAP: &str = "/Profile/Profile Information/ProfileMap";
+
+const LOCATION_DATA: &str = "/Your Activity/Most Recent Location Data/LocationData";
+
+// Dropped wherever they are. Field names are compared lowercased and without spaces or underscores
+const GPS_FIELDS: [&str; 4] = ["gps", "gpsdata", "latitude", "longitude"];
+
+// Contact details and addresses, dropped from "Auto Fill" and the profile
+const DROPPED_FIELDS: [&str; 12] = [
+    "PhoneNumber",
+    "Email",
+    "FirstName",
+    "LastName",
+    "Address",
+    "ZipCode",
+    "Unit",
+    "City",
+    "State",
+    "emailAddress",
+    "telephoneNumber",
+    "birthDate",
+];
+
+pub struct AnonymizeOptions {
+    // Mixed into the pseudonyms, so that they can't be found by hashing well-known user names.
+    // Keeping the same salt gives the same pseudonyms across exports
+    pub salt: String,
+    // Replaces the text of messages, comments and the bio
+    pub remove_text: bool,
+}
+
+// The same name always gives the same pseudonym, so chats, followers and the account's own
+// messages still match each other
+pub fn pseudonym(name: &str, salt: &str) -> String {
+    let hash = Sha256::new().chain_update(salt).chain_update(name).finalize();
+    let hex: String = hash.iter().take(6).map(|byte| format!("{:02x}", byte)).collect();
+    format!("user_{}", hex)
+}
+
+pub fn anonymize(data: &Value, options: &AnonymizeOptions) -> Value {
+    let mut data = data.clone();
+    let hide = |name: &str| pseudonym(name, &options.salt);
+
+    for section in ["/Profile/Auto Fill", PROFILE_INFO, PROFILE_MAP] {
+        if let Some(fields) = data.pointer_mut(section).and_then(|fields| fields.as_object_mut()) {
+            fields.retain(|field, _| !DROPPED_FIELDS.contains(&field.as_str()));
+        }
+    }
+    for section in [PROFILE_INFO, PROFILE_MAP] {
+        if let Some(info) = data.pointer_mut(section) {
+            replace_str(info, "userName", hide);
+            for platform in records_mut(info.get_mut("PlatformInfo")) {
+                replace_str(platform, "Name", hide);
+            }
+            if options.remove_text {
+                replace_str(info, "bioDescription", |_| String::from(REMOVED_TEXT));
+            }
+        }
+    }
+
+    // Older exports keep the follow lists under "Your Activity"
+    for root in ["Profile", "Your Activity"] {
+        for (section, list) in [("Follower List", "FansList"), ("Following List", "Following")] {
+            for entry in records_mut(data.pointer_mut(&format!("/{}/{}/{}", root, section, list))) {
+                replace_str(entry, "UserName", hide);
+                replace_str(entry, "Username", hide);
+            }
+        }
+    }
+
+    let chats = data.pointer_mut("/Direct Message/Direct Messages/ChatHistory");
+    if let Some(chats) = chats.and_then(|chats| chats.as_object_mut()) {
+        let renamed = std::mem::take(chats).into_iter().map(|(chat, mut messages)| {
+            for message in records_mut(Some(&mut messages)) {
+                replace_str(message, "From", hide);
+                if options.remove_text {
+                    replace_str(message, "Content", |_| String::from(REMOVED_TEXT));
+                }
+            }
+            (chat_name(&chat, hide), messages)
+        });
+        *chats = renamed.collect();
+    }
+
+    // Watched lives are stored in a map keyed by room ID, older exports use a list
+    for live in entries_mut(data.pointer_mut("/Tiktok Live/Watch Live History/WatchLiveMap")) {
+        replace_str(live, "Link", |link| live_link(link, hide));
+        for key in ["HostName", "Host", "AnchorName"] {
+            replace_str(live, key, hide);
+        }
+        if options.remove_text {
+            for comment in records_mut(live.get_mut("Comments")) {
+                replace_str(comment, "CommentContent", |_| String::from(REMOVED_TEXT));
+            }
+        }
+    }
+    for gift in records_mut(data.pointer_mut("/Your Activity/Purchases/SendGifts/SendGifts")) {
+        replace_str(gift, "UserName", hide);
+    }
+
+    for login in records_mut(data.pointer_mut("/Your Activity/Login History/LoginHistoryList")) {
+        replace_str(login, "IP", truncate_ip);
+    }
+    // Only the date of the most recent location is kept
+    if let Some(location) = data.pointer_mut(LOCATION_DATA).and_then(|location| location.as_object_mut()) {
+        location.retain(|field, _| field == "Date");
+    }
+    drop_gps(&mut data);
+
+    if options.remove_text {
+        for comment in records_mut(data.pointer_mut("/Comment/Comments/CommentsList")) {
+            replace_str(comment, "comment", |_| String::from(REMOVED_TEXT));
+        }
+    }
+
+    data
+}
+
+// "Chat History with bob:" -> "Chat History with user_1a2b3c4d5e6f:"
+fn chat_name(chat: &str, hide: impl Fn(&str) -> String) -> String {
+    match chat.strip_prefix(CHAT_PREFIX) {
+        Some(name) => format!("{}{}:", CHAT_PREFIX, hide(name.strip_suffix(':').unwrap_or(name))),
+        None => hide(chat),
+    }
+}
+
+// "https://www.tiktok.com/@alex_0/live" -> "https://www.tiktok.com/@user_1a2b3c4d5e6f/live"
+fn live_link(link: &str, hide: impl Fn(&str) -> String) -> String {
+    let Some(start) = link.find('@').map(|at| at + 1) else {
+        return link.to_string();
+    };
+    let end = link[start..].find(['/', '?']).map_or(link.len(), |end| start + end);
+    if start == end {
+        return link.to_string();
+    }
+    format!("{}{}{}", &link[..start], hide(&link[start..end]), &link[end..])
+}
+
+fn drop_gps(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|field, _| {
+                let normalized: String = field.chars().filter(|c| !c.is_whitespace() && *c != '_').collect();
+                !GPS_FIELDS.contains(&normalized.to_lowercase().as_str())
+            });
+            map.values_mut().for_each(drop_gps);
+        }
+        Value::Array(list) => list.iter_mut().for_each(drop_gps),
+        _ => {}
+    }
+}
+
+// Keeps the network but not the device: 85.1.2.3 -> 85.1.2.0, 2a01:cb00:1:2::5 -> 2a01:cb00:1::
+fn truncate_ip(ip: &str) -> String {
+    if ip.contains(':') {
+        let groups: Vec<&str> = ip.split(':').take(3).take_while(|group| !group.is_empty()).collect();
+        return format!("{}::", groups.join(":"));
+    }
+    let mut parts: Vec<&str> = ip.split('.').collect();
+    if parts.len() != 4 {
+        return String::new();
+    }
+    parts[3] = "0";
+    parts.join(".")
+}
+
+// Sections missing from the export are left out rather than created empty
+fn records_mut(list: Option<&mut Value>) -> impl Iterator<Item = &mut Value> {
+    list.and_then(|list| list.as_array_mut()).into_iter().flatten()
+}
+
+// The records of a list, or the values of a map keyed by ID
+fn entries_mut(entries: Option<&mut Value>) -> Vec<&mut Value> {
+    match entries {
+        Some(Value::Object(map)) => map.values_mut().collect(),
+        Some(Value::Array(list)) => list.iter_mut().collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Placeholders like "N/A" and "None" are kept, as they hold no information
+fn replace_str(entry: &mut Value, key: &str, replace: impl Fn(&str) -> String) {
+    if let Some(Value::String(text)) = entry.get_mut(key) {
+        if !text.is_empty() && text != "N/A" && text != "None" {
+            *text = replace(text);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// archive.rs
+// archive.rs is in charge of :
+// - keeping a local history of an account in a directory of gzip-compressed JSON snapshots
+// - storing only the records each new export adds, so a record is never archived twice
+// - rebuilding one export spanning every snapshot, to get lifetime statistics beyond TikTok's retention
+
+use chrono::DateTime;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::merge::{merge_newest_first, remove_known_records};
+use crate::{inventory, profile_info, window::latest_activity};
+
+const SNAPSHOT_EXTENSION: &str = ".json.gz";
+
+#[derive(Serialize)]
+pub struct Snapshot {
+    pub file: String,
+    // Records added by this snapshot
+    pub records: usize,
+    pub first_date: Option<String>,
+    pub last_date: Option<String>,
+}
+
+pub enum Ingested {
+    Added(Snapshot),
+    // Every record of the export was already archived, no snapshot was written
+    AlreadyArchived,
+}
+
+pub struct Archive {
+    dir: PathBuf,
+}
+
+impl Archive {
+    // Creates the directory if it doesn't exist yet
+    pub fn open(dir: impl AsRef<Path>) -> Result<Archive, String> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|err| format!("could not create {} - {}", dir.display(), err))?;
+        Ok(Archive { dir })
+    }
+
+    pub fn ingest(&self, export: &Value) -> Result<Ingested, String> {
+        let mut snapshot = export.clone();
+        let (records, profile_changed) = match self.lifetime()? {
+            Some(lifetime) => {
+                check_same_account(&lifetime, export)?;
+                let profile_changed = profile_info(&lifetime) != profile_info(export);
+                (remove_known_records(&lifetime, &mut snapshot), profile_changed)
+            }
+            None => (remove_known_records(&Value::Null, &mut snapshot), true),
+        };
+        // An export with no new record is still kept when its profile (bio, names, counts...) changed
+        if records == 0 && !profile_changed {
+            return Ok(Ingested::AlreadyArchived);
+        }
+
+        // Named after the latest activity of the export, so snapshots sort chronologically
+        let latest = latest_activity(export);
+        let stamp = DateTime::from_timestamp(latest, 0)
+            .map(|date| date.format("%Y%m%d-%H%M%S").to_string())
+            .unwrap_or_default();
+        let mut path = self.dir.join(format!("snapshot-{}{}", stamp, SNAPSHOT_EXTENSION));
+        let mut duplicate = 1;
+        while path.exists() {
+            duplicate += 1;
+            path = self.dir.join(format!("snapshot-{}-{}{}", stamp, duplicate, SNAPSHOT_EXTENSION));
+        }
+        write_snapshot(&path, &snapshot)?;
+
+        Ok(Ingested::Added(describe(&path, &snapshot, records)))
+    }
+
+    // One export holding every archived record, or None if the archive is empty.
+    // The profile comes from the most recent snapshot, even when it holds no dated record
+    pub fn lifetime(&self) -> Result<Option<Value>, String> {
+        let snapshots = self
+            .snapshot_files()?
+            .iter()
+            .rev()
+            .map(|path| read_snapshot(path))
+            .collect::<Result<Vec<Value>, String>>()?;
+        Ok(merge_newest_first(snapshots))
+    }
+
+    pub fn snapshots(&self) -> Result<Vec<Snapshot>, String> {
+        self.snapshot_files()?
+            .iter()
+            .map(|path| {
+                let snapshot = read_snapshot(path)?;
+                let records = remove_known_records(&Value::Null, &mut snapshot.clone());
+                Ok(describe(path, &snapshot, records))
+            })
+            .collect()
+    }
+
+    fn snapshot_files(&self) -> Result<Vec<PathBuf>, String> {
+        let entries = fs::read_dir(&self.dir).map_err(|err| format!("could not read {} - {}", self.dir.display(), err))?;
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.to_string_lossy().ends_with(SNAPSHOT_EXTENSION))
+            .collect();
+        // "snapshot-<date>-<time>-2" was written after "snapshot-<date>-<time>", and before "snapshot-<date>-<time>-10"
+        files.sort_by_key(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let name = name.trim_end_matches(SNAPSHOT_EXTENSION);
+            let parts: Vec<&str> = name.splitn(4, '-').collect();
+            let duplicate = parts.get(3).and_then(|number| number.parse::<u32>().ok()).unwrap_or(1);
+            (parts[..parts.len().min(3)].join("-"), duplicate)
+        });
+        Ok(files)
+    }
+}
+
+fn check_same_account(archived: &Value, export: &Value) -> Result<(), String> {
+    let username = |data: &Value| profile_info(data)["userName"].as_str().map(String::from);
+    match (username(archived), username(export)) {
+        (Some(archived), Some(export)) if archived != export => Err(format!(
+            "the archive holds the data of {}, not of {}",
+            archived, export
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn describe(path: &Path, snapshot: &Value, records: usize) -> Snapshot {
+    let (first, last) = inventory::date_range(snapshot);
+    Snapshot {
+        file: path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+        records,
+        first_date: first.map(|(_, date)| date),
+        last_date: last.map(|(_, date)| date),
+    }
+}
+
+fn write_snapshot(path: &Path, snapshot: &Value) -> Result<(), String> {
+    let error = |err: std::io::Error| format!("could not write {} - {}", path.display(), err);
+    let file = File::create(path).map_err(error)?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    serde_json::to_writer(&mut encoder, snapshot).map_err(|err| error(err.into()))?;
+    encoder.finish().map_err(error)?.flush().map_err(error)
+}
+
+fn read_snapshot(path: &Path) -> Result<Value, String> {
+    let file = File::open(path).map_err(|err| format!("could not read {} - {}", path.display(), err))?;
+    serde_json::from_reader(GzDecoder::new(BufReader::new(file)))
+        .map_err(|err| format!("{} is not a valid snapshot - {}", path.display(), err))
+}
@@ -0,0 +1,121 @@
+// charts.rs
+// charts.rs is in charge of :
+// - drawing the text charts of the report (bar charts and textplots line charts) into a fmt::Write
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Write};
+use textplots::{Chart, Plot, Shape};
+
+pub fn dms_chart(out: &mut dyn Write, dms: &HashMap<String, usize>) -> fmt::Result {
+    distribution_chart(out, "DM Distribution", dms)
+}
+
+// Horizontal bar chart of the 10 biggest entries of a name -> count map
+pub fn distribution_chart(out: &mut dyn Write, title: &str, counts: &HashMap<String, usize>) -> fmt::Result {
+    if counts.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "\n--- {} ---", title)?;
+    let mut sorted_counts: Vec<(&String, &usize)> = counts.iter().collect();
+    sorted_counts.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let top_counts: Vec<_> = sorted_counts.iter().take(10).collect();
+    let max_len = top_counts.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    // Find the max count to scale the bars relative to the top entry
+    let max_count = top_counts.first().map(|(_, count)| **count).unwrap_or(1) as f64;
+    let max_bar_width = 40.0; // Max width for a bar in characters
+
+    for (name, count) in top_counts {
+        let bar_len = ((**count as f64 / max_count) * max_bar_width) as usize;
+        let bar = "█".repeat(bar_len);
+        writeln!(out, "{:<width$}: |{} {}", name, bar, count, width = max_len)?;
+    }
+    writeln!(out, "{}\n", "-".repeat(title.len() + 8))
+}
+
+// Vertical list of bars, one per month, in chronological order
+pub fn monthly_chart(out: &mut dyn Write, title: &str, per_month: &BTreeMap<String, usize>) -> fmt::Result {
+    let rows: Vec<(&String, f64, String)> = per_month
+        .iter()
+        .map(|(month, count)| (month, *count as f64, count.to_string()))
+        .collect();
+    month_bars(out, title, &rows)
+}
+
+pub fn monthly_amount_chart(out: &mut dyn Write, title: &str, per_month: &BTreeMap<String, f64>) -> fmt::Result {
+    let rows: Vec<(&String, f64, String)> = per_month
+        .iter()
+        .map(|(month, amount)| (month, *amount, format!("{:.2}", amount)))
+        .collect();
+    month_bars(out, title, &rows)
+}
+
+fn month_bars(out: &mut dyn Write, title: &str, rows: &[(&String, f64, String)]) -> fmt::Result {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "\n--- {} ---", title)?;
+    let max_value = rows.iter().map(|(_, value, _)| *value).fold(0.0, f64::max);
+    let max_value = if max_value > 0.0 { max_value } else { 1.0 };
+    let max_bar_width = 40.0;
+
+    for (month, value, label) in rows {
+        let bar_len = ((value / max_value) * max_bar_width) as usize;
+        writeln!(out, "{}: |{} {}", month, "█".repeat(bar_len), label)?;
+    }
+    writeln!(out, "{}\n", "-".repeat(title.len() + 8))
+}
+
+pub fn time_spent_chart(out: &mut dyn Write, time_in_minutes: usize) -> fmt::Result {
+    writeln!(out, "\n--- Daily Time Spent (minutes) ---")?;
+    let your_time = [(0.0, 0.0), (20.0, time_in_minutes as f32), (40.0, 0.0)];
+    let world_average = [(50.0, 0.0), (70.0, 80.0), (90.0, 0.0)]; // Average time spent in World
+    let (your_time, world_average) = (Shape::Bars(&your_time), Shape::Bars(&world_average));
+    let mut chart = Chart::new(120, 60, 0.0, 100.0);
+    draw(out, chart.lineplot(&your_time).lineplot(&world_average))?;
+    writeln!(out, "   Your Time ({})   |   Average in World (~80)", time_in_minutes)?;
+    writeln!(out, "------------------------------------\n")
+}
+
+pub fn follow_growth_chart(
+    out: &mut dyn Write,
+    followers_per_month: &BTreeMap<String, usize>,
+    following_per_month: &BTreeMap<String, usize>,
+) -> fmt::Result {
+    let months: BTreeSet<&String> = followers_per_month.keys().chain(following_per_month.keys()).collect();
+    if months.len() < 2 {
+        return Ok(());
+    }
+
+    // Running totals, so the chart shows how both lists grew over time
+    let mut followers_total = 0.0;
+    let mut following_total = 0.0;
+    let mut followers_points = Vec::new();
+    let mut following_points = Vec::new();
+    for (index, month) in months.iter().enumerate() {
+        followers_total += *followers_per_month.get(*month).unwrap_or(&0) as f32;
+        following_total += *following_per_month.get(*month).unwrap_or(&0) as f32;
+        followers_points.push((index as f32, followers_total));
+        following_points.push((index as f32, following_total));
+    }
+
+    writeln!(out, "\n--- Follow Growth (cumulative, per month) ---")?;
+    let (followers, following) = (Shape::Lines(&followers_points), Shape::Steps(&following_points));
+    let mut chart = Chart::new(120, 60, 0.0, (months.len() - 1) as f32);
+    draw(out, chart.lineplot(&followers).lineplot(&following))?;
+    writeln!(
+        out,
+        "   Lines: followers ({})   |   Steps: following ({})",
+        followers_total, following_total
+    )?;
+    writeln!(out, "   From {} to {}", months.first().unwrap(), months.last().unwrap())?;
+    writeln!(out, "---------------------------------------------\n")
+}
+
+// Same as Chart::display, which can only print to the console
+fn draw(out: &mut dyn Write, chart: &mut Chart) -> fmt::Result {
+    chart.axis();
+    chart.figures();
+    writeln!(out, "{}", chart)
+}
@@ -0,0 +1,254 @@
+// cli.rs
+// cli.rs is in charge of :
+// - describing the commands and options of the program, which clap turns into an argument parser and the --help pages
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "tiktok_json_analyzer",
+    version,
+    about = "Reads the TikTok JSON data export and calculates statistics",
+    after_help = "Read the documentation at https://github.com/Elazrod56/tiktok_json_analyzer#readme"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Output format, each command lists the formats it supports
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<Format>,
+
+    /// Only print the results, without the banner and progress messages
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Hide message content, the names of other people, links, precise locations and profile details, to share the results safely
+    #[arg(long, global = true)]
+    pub redact: bool,
+
+    /// Don't color the output (also turned off by NO_COLOR or when the output isn't a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Print the statistics of an export (formats: text, json)
+    Report {
+        #[command(flatten)]
+        input: Input,
+
+        /// Sections to print, in order and comma separated: "dms,logins" prints these two,
+        /// "-location" every section but one and "dms,..." every section with the DMs first
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        section: Vec<String>,
+    },
+    /// List everything the export contains and what the analyzer uses (formats: text, json, csv)
+    Inventory {
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Write the statistics to a file, or to the standard output (formats: json, csv)
+    Export {
+        #[command(flatten)]
+        input: Input,
+
+        /// File to write, the standard output is used if it is missing
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Compare the statistics of two exports of the same account (formats: text, json)
+    Diff {
+        /// Path to the older user_data.json file
+        old: String,
+
+        /// Path to the newer user_data.json file
+        new: String,
+
+        #[command(flatten)]
+        options: AnalysisArgs,
+    },
+    /// Combine several exports of the same account into one export, without duplicated records (formats: json)
+    Merge {
+        /// Paths to the user_data.json files, in any order
+        #[arg(required = true, num_args = 2..)]
+        files: Vec<String>,
+
+        /// File to write, the standard output is used if it is missing
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Write a copy of an export without personal information, to share it for research (formats: json)
+    Anonymize {
+        /// Path to the user_data.json file
+        file: String,
+
+        /// Secret mixed into the pseudonyms, without it common user names can be guessed from their pseudonym.
+        /// Use the same salt for every export of a study to get the same pseudonyms
+        #[arg(long, default_value = "")]
+        salt: String,
+
+        /// Also replace the text of messages, comments and the bio
+        #[arg(long)]
+        remove_text: bool,
+
+        /// File to write, the standard output is used if it is missing
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Compare the statistics of every export of a directory, with percentiles (formats: text, json, csv)
+    Compare {
+        /// Directory holding one JSON export per account
+        dir: PathBuf,
+
+        #[command(flatten)]
+        options: AnalysisArgs,
+
+        /// File to write, the standard output is used if it is missing
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Keep a local history of the account, to get statistics beyond what TikTok retains
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+    /// Count the activity of every section day by day or month by month (formats: text, json, csv)
+    Timeline {
+        #[command(flatten)]
+        input: Input,
+
+        /// Length of the periods
+        #[arg(long, value_enum, default_value_t = Step::Month)]
+        by: Step,
+    },
+    /// Write a fake export with realistic activity, to test or demo the analyzer (formats: json)
+    Generate {
+        /// Seed of the generator, the same seed and options always give the same export
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// User name of the fake account
+        #[arg(long, default_value = "demo.user")]
+        username: String,
+
+        /// Number of other accounts (followers, follows, chat partners and live hosts)
+        #[arg(long, default_value_t = 40)]
+        users: usize,
+
+        /// First day of the export (2024-01-01)
+        #[arg(long, default_value = "2024-01-01")]
+        start: String,
+
+        /// Number of days the export covers
+        #[arg(long, default_value_t = 90)]
+        days: u32,
+
+        /// How much the fake account uses TikTok
+        #[arg(long, value_enum, default_value_t = Usage::Regular)]
+        usage: Usage,
+
+        /// File to write, the standard output is used if it is missing
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Check that a file can be analyzed and list what is missing or unreadable (formats: text, json)
+    Validate {
+        /// Path to the user_data.json file
+        file: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArchiveAction {
+    /// Add exports to the archive, only keeping the records it doesn't have yet (formats: text, json)
+    Add {
+        /// Directory of the archive, created if it doesn't exist
+        dir: PathBuf,
+
+        /// Paths to the user_data.json files
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// List the snapshots of the archive (formats: text, json)
+    List {
+        /// Directory of the archive
+        dir: PathBuf,
+    },
+    /// Print the lifetime statistics of every archived record (formats: text, json)
+    Report {
+        /// Directory of the archive
+        dir: PathBuf,
+
+        #[command(flatten)]
+        options: AnalysisArgs,
+
+        /// Sections to print, in the same form as for the report command
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        section: Vec<String>,
+    },
+    /// Write every archived record as a single export (formats: json)
+    Export {
+        /// Directory of the archive
+        dir: PathBuf,
+
+        /// File to write, the standard output is used if it is missing
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+pub struct Input {
+    /// Path to the user_data.json file
+    pub file: String,
+
+    #[command(flatten)]
+    pub options: AnalysisArgs,
+}
+
+#[derive(Args)]
+pub struct AnalysisArgs {
+    /// Start of the period to analyze: a year (2024), a month (2024-03), a day (2024-03-15) or a duration (30d, 4w, 6m, 1y)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// End of the period to analyze, in the same forms as --since
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Time zone used for days and months, e.g. "Europe/Paris" or "+02:00" (defaults to the local one)
+    #[arg(long, visible_alias = "tz")]
+    pub timezone: Option<String>,
+
+    /// Date the averages are measured up to: "latest" (latest event of the period), "export" (latest event of the
+    /// whole export, or the modification time of the file when the export holds no date) or a date
+    #[arg(long, default_value = "latest")]
+    pub reference: String,
+
+    /// Number of decimals the averages are rounded to
+    #[arg(long, default_value_t = 2)]
+    pub decimals: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Step {
+    Day,
+    Month,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Usage {
+    Light,
+    Regular,
+    Heavy,
+}
@@ -0,0 +1,148 @@
+// compare.rs
+// compare.rs is in charge of :
+// - putting the main statistics of several accounts side by side, one row per account
+// - telling where each value stands among all the accounts, with percentiles per metric
+// It is meant for studies where participants share their exports
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{rates::round, Statistics, AVERAGE_VIDEO_SECONDS};
+
+// Name of the metric and how it is read from the statistics
+type Metric = (&'static str, fn(&Statistics) -> f64);
+
+const METRICS: [Metric; 12] = [
+    ("launches_per_day", |s| s.logins.rate.per_day),
+    ("videos_watched_per_day", |s| s.watched.rate.per_day),
+    ("likes_per_day", |s| s.likes_left.rate.per_day),
+    ("minutes_per_day", |s| s.watched.rate.per_day * AVERAGE_VIDEO_SECONDS / 60.0),
+    ("liked_percentage", |s| s.funnel.liked_percentage),
+    ("favorited_percentage", |s| s.funnel.favorited_percentage),
+    ("shared_percentage", |s| s.funnel.shared_percentage),
+    ("shares_per_watched_video", |s| s.shares.shares_per_watched_video),
+    ("comments", |s| s.comments as f64),
+    ("direct_messages", |s| s.dms.values().sum::<usize>() as f64),
+    ("followers", |s| s.follows.followers as f64),
+    ("following", |s| s.follows.following as f64),
+];
+
+const PERCENTILES: [u32; 5] = [10, 25, 50, 75, 90];
+
+#[derive(Serialize)]
+pub struct AccountRow {
+    // Name the account was given in the comparison, usually the file name
+    pub account: String,
+    pub username: String,
+    pub values: BTreeMap<String, f64>,
+}
+
+#[derive(Serialize)]
+pub struct MetricSummary {
+    pub metric: String,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    // "p10", "p25", "p50", "p75" and "p90"
+    pub percentiles: BTreeMap<String, f64>,
+}
+
+#[derive(Serialize)]
+pub struct Comparison {
+    pub metrics: Vec<String>,
+    pub accounts: Vec<AccountRow>,
+    pub summary: Vec<MetricSummary>,
+}
+
+impl Comparison {
+    pub fn csv_header(&self) -> Vec<&str> {
+        let mut header = vec!["account", "username"];
+        header.extend(self.metrics.iter().map(String::as_str));
+        header
+    }
+
+    // One row per account, followed by one row per statistic of the summary ("min", "p50", "mean"...)
+    pub fn csv_rows(&self) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = self
+            .accounts
+            .iter()
+            .map(|row| {
+                let mut cells = vec![row.account.clone(), row.username.clone()];
+                cells.extend(self.metrics.iter().map(|metric| row.values[metric].to_string()));
+                cells
+            })
+            .collect();
+
+        let mut labels = vec![String::from("min")];
+        labels.extend(PERCENTILES.iter().map(|p| format!("p{}", p)));
+        labels.extend([String::from("max"), String::from("mean")]);
+        for label in labels {
+            let mut cells = vec![label.clone(), String::new()];
+            cells.extend(self.summary.iter().map(|summary| {
+                let value = match label.as_str() {
+                    "min" => summary.min,
+                    "max" => summary.max,
+                    "mean" => summary.mean,
+                    percentile => summary.percentiles[percentile],
+                };
+                value.to_string()
+            }));
+            rows.push(cells);
+        }
+        rows
+    }
+}
+
+// Accounts are (name, statistics) pairs, kept in the given order
+pub fn compare_accounts(accounts: &[(String, Statistics)], decimals: usize) -> Comparison {
+    let rows: Vec<AccountRow> = accounts
+        .iter()
+        .map(|(account, statistics)| AccountRow {
+            account: account.clone(),
+            username: statistics.username.clone(),
+            values: METRICS
+                .iter()
+                .map(|(name, read)| (name.to_string(), round(read(statistics), decimals)))
+                .collect(),
+        })
+        .collect();
+
+    let summary = METRICS
+        .iter()
+        .map(|(name, _)| {
+            let mut values: Vec<f64> = rows.iter().map(|row| row.values[*name]).collect();
+            values.sort_by(f64::total_cmp);
+            summarize(name, &values, decimals)
+        })
+        .collect();
+
+    Comparison {
+        metrics: METRICS.iter().map(|(name, _)| name.to_string()).collect(),
+        accounts: rows,
+        summary,
+    }
+}
+
+fn summarize(metric: &str, sorted: &[f64], decimals: usize) -> MetricSummary {
+    let mean = if sorted.is_empty() { 0.0 } else { sorted.iter().sum::<f64>() / sorted.len() as f64 };
+    MetricSummary {
+        metric: metric.to_string(),
+        min: sorted.first().copied().unwrap_or(0.0),
+        max: sorted.last().copied().unwrap_or(0.0),
+        mean: round(mean, decimals),
+        percentiles: PERCENTILES
+            .iter()
+            .map(|p| (format!("p{}", p), round(percentile(sorted, *p), decimals)))
+            .collect(),
+    }
+}
+
+// Linear interpolation between the two closest values, like most spreadsheets do
+fn percentile(sorted: &[f64], percentile: u32) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = percentile as f64 / 100.0 * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
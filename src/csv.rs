@@ -0,0 +1,54 @@
+// csv.rs
+// csv.rs is in charge of :
+// - writing rows of values as CSV, quoting the fields that need it
+// - flattening nested JSON (like a serialized Statistics) into "path,value" rows
+
+use serde_json::Value;
+
+pub fn to_csv(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut csv = csv_line(header.iter().map(|field| field.to_string()));
+    for row in rows {
+        csv.push_str(&csv_line(row.iter().cloned()));
+    }
+    csv
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let fields: Vec<String> = fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+// Every leaf becomes a row: {"logins": {"openings": 3}} gives ("logins.openings", "3").
+// Array items are numbered from 0, and nulls are written as empty fields
+pub fn flatten_json(value: &Value) -> Vec<(String, String)> {
+    let mut rows = Vec::new();
+    flatten_into(value, String::new(), &mut rows);
+    rows
+}
+
+fn flatten_into(value: &Value, path: String, rows: &mut Vec<(String, String)>) {
+    let child_path = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten_into(child, child_path(key), rows);
+            }
+        }
+        Value::Array(list) => {
+            for (index, child) in list.iter().enumerate() {
+                flatten_into(child, child_path(&index.to_string()), rows);
+            }
+        }
+        Value::Null => rows.push((path, String::new())),
+        Value::String(text) => rows.push((path, text.clone())),
+        other => rows.push((path, other.to_string())),
+    }
+}
@@ -2,29 +2,145 @@
 // date_utils.rs is in charge of :
 // - providing functions that help calculate the time (in days) between two dates
 // - these functions are used in lib.rs - for instance they're used when we need to calculate likes/day or videos/day...
+// - turning the UTC dates of the export into calendar dates of the user's time zone
 
-use chrono::{NaiveDateTime, TimeZone};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
 
-pub fn date_to_unix_timestamp(date_str: &str) -> Option<i64> {
-    let date_time = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S").ok()?;
-    let timestamp = chrono::Utc
-        .timestamp_millis_opt(date_time.and_utc().timestamp_millis())
-        .unwrap()
-        .timestamp();
-    Some(timestamp)
-}
-
-pub fn days_between(reference_timestamp: i64, date_str: &str) -> Option<usize> {
-    let past_timestamp = match date_to_unix_timestamp(date_str) {
-        Some(ts) => ts,
-        None => return None,
+// TikTok writes every date of the export in UTC. The user's time zone is only used to tell
+// which calendar day, month or hour a date falls on, and to read the dates the user types in
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum UserTimeZone {
+    #[default]
+    Local,
+    Named(Tz),
+    Fixed(FixedOffset),
+}
+
+impl UserTimeZone {
+    // Accepts "local", an IANA name ("Europe/Paris", "UTC") or a fixed offset ("+02:00", "-0530", "+5")
+    pub fn parse(zone: &str) -> Result<UserTimeZone, String> {
+        let zone = zone.trim();
+        if zone.eq_ignore_ascii_case("local") {
+            return Ok(UserTimeZone::Local);
+        }
+        if let Ok(named) = zone.parse::<Tz>() {
+            return Ok(UserTimeZone::Named(named));
+        }
+        parse_offset(zone)
+            .map(UserTimeZone::Fixed)
+            .ok_or_else(|| format!("\"{}\" is not a known time zone or UTC offset", zone))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            UserTimeZone::Local => format!("local time (UTC{})", Local::now().offset()),
+            UserTimeZone::Named(named) => named.name().to_string(),
+            UserTimeZone::Fixed(offset) => format!("UTC{}", offset),
+        }
+    }
+
+    // The offset depends on the date because of daylight saving time
+    fn offset_at(&self, timestamp: i64) -> FixedOffset {
+        let utc = DateTime::from_timestamp(timestamp, 0).unwrap_or_default().naive_utc();
+        match self {
+            UserTimeZone::Local => Local.offset_from_utc_datetime(&utc).fix(),
+            UserTimeZone::Named(named) => named.offset_from_utc_datetime(&utc).fix(),
+            UserTimeZone::Fixed(offset) => *offset,
+        }
+    }
+
+    pub fn local_date_time(&self, timestamp: i64) -> NaiveDateTime {
+        let utc = DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+        utc.with_timezone(&self.offset_at(timestamp)).naive_local()
+    }
+
+    // Turns a date typed by the user (so in their time zone) into a timestamp
+    pub fn timestamp_of_local(&self, local: NaiveDateTime) -> Option<i64> {
+        let convert = |local: NaiveDateTime| match self {
+            UserTimeZone::Local => Local.from_local_datetime(&local).earliest().map(|d| d.timestamp()),
+            UserTimeZone::Named(named) => named.from_local_datetime(&local).earliest().map(|d| d.timestamp()),
+            UserTimeZone::Fixed(offset) => offset.from_local_datetime(&local).earliest().map(|d| d.timestamp()),
+        };
+        // A date skipped by a daylight saving change doesn't exist, the next hour is used instead
+        convert(local).or_else(|| convert(local + chrono::Duration::hours(1)))
+    }
+}
+
+fn parse_offset(offset: &str) -> Option<FixedOffset> {
+    let offset = offset.strip_prefix("UTC").or_else(|| offset.strip_prefix("GMT")).unwrap_or(offset);
+    let sign = match offset.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        3 | 4 => {
+            let split = digits.len() - 2;
+            (digits[..split].parse::<i32>().ok()?, digits[split..].parse::<i32>().ok()?)
+        }
+        _ => return None,
     };
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+// TikTok has used "2024-03-01 10:00:00" (UTC) for most lists, but also ISO 8601 dates and epoch seconds
+pub fn parse_date(date_str: &str) -> Option<DateTime<Utc>> {
+    let date_str = date_str.trim();
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S") {
+        return Some(date_time.and_utc());
+    }
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(date_str) {
+        return Some(date_time.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.fZ"] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(date_str, format) {
+            return Some(date_time.and_utc());
+        }
+    }
+    // Epoch seconds, or milliseconds for the 13 digits ones
+    if !date_str.is_empty() && date_str.chars().all(|c| c.is_ascii_digit()) {
+        let number = date_str.parse::<i64>().ok()?;
+        return match date_str.len() {
+            9 | 10 => Utc.timestamp_opt(number, 0).single(),
+            13 => Utc.timestamp_millis_opt(number).single(),
+            _ => None,
+        };
+    }
+    None
+}
+
+pub fn date_to_unix_timestamp(date_str: &str) -> Option<i64> {
+    parse_date(date_str).map(|date_time| date_time.timestamp())
+}
+
+// Counts calendar days in the user's time zone, so 23:00 -> 01:00 the next day is one day
+pub fn days_between(reference_timestamp: i64, date_str: &str, tz: &UserTimeZone) -> Option<usize> {
+    let past_timestamp = date_to_unix_timestamp(date_str)?;
 
     if reference_timestamp < past_timestamp {
         return Some(0);
     }
 
-    let duration_secs = (reference_timestamp - past_timestamp) as u64;
-    let days = duration_secs / (24 * 60 * 60);
-    Some(days as usize)
+    let reference_day: NaiveDate = tz.local_date_time(reference_timestamp).date();
+    let past_day: NaiveDate = tz.local_date_time(past_timestamp).date();
+    Some((reference_day - past_day).num_days() as usize)
+}
+
+// Turns a TikTok date into a "YYYY-MM" key of the user's time zone, used to group activity by month
+pub fn month_key(date_str: &str, tz: &UserTimeZone) -> Option<String> {
+    let timestamp = date_to_unix_timestamp(date_str)?;
+    Some(month_of(timestamp, tz))
+}
+
+pub fn month_of(timestamp: i64, tz: &UserTimeZone) -> String {
+    tz.local_date_time(timestamp).format("%Y-%m").to_string()
 }
@@ -0,0 +1,309 @@
+// diff.rs
+// diff.rs is in charge of :
+// - comparing two exports of the same account, usually requested a few weeks apart
+// - telling what happened in between: new activity, new followers and unfollowers, deleted posts,
+// changed profile fields and how the averages moved
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+
+use crate::follows::follow_list;
+use crate::{date_utils, profile_info, record_date, report::paint, AnalysisOptions, Rate, Statistics};
+
+#[derive(Serialize)]
+pub struct CountChange {
+    pub name: String,
+    pub old: usize,
+    pub new: usize,
+    // Records of the new export dated after the last record of the old one. TikTok drops old records,
+    // so this is the activity between the two exports even when `new` is smaller than `old`
+    pub added: usize,
+}
+
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RateChange {
+    pub name: String,
+    pub old: f64,
+    pub new: f64,
+    pub change: f64,
+}
+
+#[derive(Serialize)]
+pub struct DeletedPost {
+    pub date: String,
+    pub link: String,
+}
+
+#[derive(Serialize)]
+pub struct ExportDiff {
+    pub old_period: String,
+    pub new_period: String,
+    pub counts: Vec<CountChange>,
+    pub new_followers: Vec<String>,
+    pub unfollowers: Vec<String>,
+    pub new_following: Vec<String>,
+    pub unfollowed: Vec<String>,
+    pub deleted_posts: Vec<DeletedPost>,
+    pub profile_changes: Vec<FieldChange>,
+    pub rate_changes: Vec<RateChange>,
+}
+
+// Lists compared between the two exports. Maps (like the chat history) count the records of every entry
+const TRACKED_LISTS: [(&str, &str); 8] = [
+    ("Logins", "Your Activity > Login History > LoginHistoryList"),
+    ("Videos watched", "Your Activity > Watch History > VideoList"),
+    ("Videos liked", "Your Activity > Like List > ItemFavoriteList"),
+    ("Comments", "Comment > Comments > CommentsList"),
+    ("Direct messages", "Direct Message > Direct Messages > ChatHistory"),
+    ("Shares", "Your Activity > Share History > ShareHistoryList"),
+    ("Favorite videos", "Your Activity > Favorite Videos > FavoriteVideoList"),
+    ("Posts", "Post > Posts > VideoList"),
+];
+
+const POSTS: &str = "Post > Posts > VideoList";
+
+// Both exports are analyzed with the same options. With the latest event as reference, each one measures
+// its averages up to its own latest event, a fixed date or the export date is shared by both
+pub fn diff_exports(old_data: &Value, new_data: &Value, options: &AnalysisOptions) -> ExportDiff {
+    // Every comparison below only sees the records of the analysis window
+    let windowed = |data: &Value| {
+        let mut data = data.clone();
+        options.window.apply(&mut data);
+        data
+    };
+    let (old_data, new_data) = (&windowed(old_data), &windowed(new_data));
+
+    let old = Statistics::build_with(old_data.clone(), options);
+    let new = Statistics::build_with(new_data.clone(), options);
+
+    let mut counts: Vec<CountChange> = TRACKED_LISTS
+        .iter()
+        .map(|(name, path)| count_change(name, &records(old_data, path), &records(new_data, path)))
+        .collect();
+
+    let (old_followers, old_following) = follow_names(old_data);
+    let (new_followers, new_following) = follow_names(new_data);
+    counts.push(CountChange {
+        name: String::from("Followers"),
+        old: old.follows.followers,
+        new: new.follows.followers,
+        added: new_followers.difference(&old_followers).count(),
+    });
+    counts.push(CountChange {
+        name: String::from("Following"),
+        old: old.follows.following,
+        new: new.follows.following,
+        added: new_following.difference(&old_following).count(),
+    });
+
+    let rate_changes = [
+        ("Launches per day", &old.logins.rate, &new.logins.rate),
+        ("Videos watched per day", &old.watched.rate, &new.watched.rate),
+        ("Likes per day", &old.likes_left.rate, &new.likes_left.rate),
+    ]
+    .into_iter()
+    .map(|(name, old, new)| rate_change(name, old, new, options.decimals))
+    .collect();
+
+    ExportDiff {
+        old_period: period_of(&old),
+        new_period: period_of(&new),
+        counts,
+        new_followers: new_followers.difference(&old_followers).cloned().collect(),
+        unfollowers: old_followers.difference(&new_followers).cloned().collect(),
+        new_following: new_following.difference(&old_following).cloned().collect(),
+        unfollowed: old_following.difference(&new_following).cloned().collect(),
+        deleted_posts: deleted_posts(old_data, new_data),
+        profile_changes: profile_changes(old_data, new_data),
+        rate_changes,
+    }
+}
+
+fn period_of(statistics: &Statistics) -> String {
+    let rate = &statistics.watched.rate;
+    match &rate.from {
+        Some(from) => format!("{} -> {}", from, rate.to),
+        None => format!("up to {}", rate.to),
+    }
+}
+
+fn records<'a>(data: &'a Value, path: &str) -> Vec<&'a Value> {
+    let list = path.split(" > ").fold(data, |value, key| &value[key]);
+    match list {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(entries) => entries
+            .values()
+            .flat_map(|entry| match entry {
+                Value::Array(items) => items.iter().collect(),
+                other => vec![other],
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn timestamp(record: &Value) -> Option<i64> {
+    record_date(record).and_then(|date| date_utils::date_to_unix_timestamp(&date))
+}
+
+fn count_change(name: &str, old: &[&Value], new: &[&Value]) -> CountChange {
+    let old_latest = old.iter().filter_map(|record| timestamp(record)).max();
+    let added = new
+        .iter()
+        .filter_map(|record| timestamp(record))
+        .filter(|ts| old_latest.is_none_or(|latest| *ts > latest))
+        .count();
+
+    CountChange {
+        name: name.to_string(),
+        old: old.len(),
+        new: new.len(),
+        added,
+    }
+}
+
+fn follow_names(data: &Value) -> (BTreeSet<String>, BTreeSet<String>) {
+    let names = |section, list| -> BTreeSet<String> {
+        follow_list(data, section, list)
+            .into_iter()
+            .map(|item| item.content)
+            .filter(|name| !name.is_empty())
+            .collect()
+    };
+    (names("Follower List", "FansList"), names("Following List", "Following"))
+}
+
+// Posts are matched on their link, or on their date when the export has no link
+fn deleted_posts(old_data: &Value, new_data: &Value) -> Vec<DeletedPost> {
+    let post_key = |post: &Value| -> String {
+        let link = post.get("Link").and_then(|link| link.as_str()).unwrap_or("");
+        if link.is_empty() {
+            record_date(post).unwrap_or_default().to_string()
+        } else {
+            link.to_string()
+        }
+    };
+    let remaining: BTreeSet<String> = records(new_data, POSTS).into_iter().map(post_key).collect();
+
+    records(old_data, POSTS)
+        .into_iter()
+        .filter(|post| !remaining.contains(&post_key(post)))
+        .map(|post| DeletedPost {
+            date: record_date(post).unwrap_or_default().to_string(),
+            link: post.get("Link").and_then(|link| link.as_str()).unwrap_or("").to_string(),
+        })
+        .collect()
+}
+
+fn profile_changes(old_data: &Value, new_data: &Value) -> Vec<FieldChange> {
+    let fields = |data: &Value| -> BTreeMap<String, String> {
+        profile_info(data)
+            .as_object()
+            .map(|info| {
+                info.iter()
+                    .filter_map(|(field, value)| match value {
+                        Value::String(text) => Some((field.clone(), text.clone())),
+                        Value::Number(_) | Value::Bool(_) => Some((field.clone(), value.to_string())),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let old = fields(old_data);
+    let new = fields(new_data);
+
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter(|field| old.get(*field) != new.get(*field))
+        .map(|field| FieldChange {
+            field: field.clone(),
+            old: old.get(field).cloned(),
+            new: new.get(field).cloned(),
+        })
+        .collect()
+}
+
+fn rate_change(name: &str, old: &Rate, new: &Rate, decimals: usize) -> RateChange {
+    RateChange {
+        name: name.to_string(),
+        old: old.per_day,
+        new: new.per_day,
+        change: crate::rates::round(new.per_day - old.per_day, decimals),
+    }
+}
+
+pub fn render_diff(diff: &ExportDiff, color: bool) -> String {
+    let mut out = String::new();
+    write_diff(diff, color, &mut out).expect("writing to a String can't fail");
+    out
+}
+
+fn write_diff(diff: &ExportDiff, color: bool, out: &mut dyn Write) -> fmt::Result {
+    let heading = |title: &str| paint(&format!("---------- {} ----------", title), "1;36", color);
+
+    writeln!(out, "Old export : {}", diff.old_period)?;
+    writeln!(out, "New export : {}", diff.new_period)?;
+
+    writeln!(out, "\n{}", heading("NEW ACTIVITY \u{1F4C8}"))?;
+    for count in diff.counts.iter() {
+        writeln!(
+            out,
+            "{} : {} -> {} ({} new since the old export)",
+            count.name, count.old, count.new, count.added
+        )?;
+    }
+
+    writeln!(out, "\n{}", heading("FOLLOWERS & FOLLOWING \u{1F465}"))?;
+    let lists = [
+        ("New followers", &diff.new_followers),
+        ("Unfollowed you", &diff.unfollowers),
+        ("New follows", &diff.new_following),
+        ("You unfollowed", &diff.unfollowed),
+    ];
+    for (title, names) in lists {
+        if names.is_empty() {
+            writeln!(out, "{} : none", title)?;
+        } else {
+            writeln!(out, "{} ({}) : {}", title, names.len(), names.join(", "))?;
+        }
+    }
+
+    writeln!(out, "\n{}", heading("DELETED POSTS \u{1F5D1}"))?;
+    if diff.deleted_posts.is_empty() {
+        writeln!(out, "No post was deleted")?;
+    }
+    for post in diff.deleted_posts.iter() {
+        writeln!(out, "  - {} ({})", post.link, post.date)?;
+    }
+
+    writeln!(out, "\n{}", heading("PROFILE \u{1F464}"))?;
+    if diff.profile_changes.is_empty() {
+        writeln!(out, "Your profile didn't change")?;
+    }
+    for change in diff.profile_changes.iter() {
+        writeln!(
+            out,
+            "{} : \"{}\" -> \"{}\"",
+            change.field,
+            change.old.as_deref().unwrap_or("(none)"),
+            change.new.as_deref().unwrap_or("(none)")
+        )?;
+    }
+
+    writeln!(out, "\n{}", heading("AVERAGES \u{2696}"))?;
+    for rate in diff.rate_changes.iter() {
+        writeln!(out, "{} : {} -> {} ({:+})", rate.name, rate.old, rate.new, rate.change)?;
+    }
+    Ok(())
+}
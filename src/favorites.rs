@@ -0,0 +1,100 @@
+// favorites.rs
+// favorites.rs is in charge of :
+// - reading the dates and links of the favorite sounds, effects, videos and hashtags
+// - telling when favoriting happened and how many favorite videos were also liked
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{count_per_month, date_field, date_info_by_timestamp, video_id, ActivityItem, DateInfo, UserTimeZone};
+
+#[derive(Serialize)]
+pub struct FavoriteCategory {
+    pub per_month: BTreeMap<String, usize>,
+    pub info: Option<DateInfo>,
+}
+
+#[derive(Serialize)]
+pub struct FavoriteDetails {
+    pub sounds: FavoriteCategory,
+    pub effects: FavoriteCategory,
+    pub videos: FavoriteCategory,
+    pub hashtags: FavoriteCategory,
+    pub per_month: BTreeMap<String, usize>,
+    pub videos_also_liked: usize,
+    pub videos_also_liked_percentage: f64,
+}
+
+pub fn favorite_details(data: &Value, tz: &UserTimeZone) -> FavoriteDetails {
+    let activity = &data["Your Activity"];
+    let sounds = favorite_items(&activity["Favorite Sounds"]["FavoriteSoundList"], "Link");
+    let effects = favorite_items(&activity["Favorite Effects"]["FavoriteEffectsList"], "EffectLink");
+    let videos = favorite_items(&activity["Favorite Videos"]["FavoriteVideoList"], "Link");
+    let hashtags = favorite_items(&activity["Favorite Hashtags"]["FavoriteHashtagList"], "Link");
+
+    let all: Vec<ActivityItem> = sounds
+        .iter()
+        .chain(effects.iter())
+        .chain(videos.iter())
+        .chain(hashtags.iter())
+        .cloned()
+        .collect();
+
+    let liked_ids: HashSet<String> = activity["Like List"]["ItemFavoriteList"]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .filter_map(|like| like.get("link").and_then(|l| l.as_str()))
+                .filter_map(video_id)
+                .collect()
+        })
+        .unwrap_or_default();
+    let videos_also_liked = videos
+        .iter()
+        .filter_map(|video| video_id(&video.content))
+        .filter(|id| liked_ids.contains(id))
+        .count();
+    let videos_also_liked_percentage = if videos.is_empty() {
+        0.0
+    } else {
+        videos_also_liked as f64 / videos.len() as f64 * 100.0
+    };
+
+    FavoriteDetails {
+        sounds: category(&sounds, tz),
+        effects: category(&effects, tz),
+        videos: category(&videos, tz),
+        hashtags: category(&hashtags, tz),
+        per_month: count_per_month(&all, tz),
+        videos_also_liked,
+        videos_also_liked_percentage,
+    }
+}
+
+fn favorite_items(list: &Value, link_key: &str) -> Vec<ActivityItem> {
+    list.as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|entry| {
+                    ActivityItem::new(
+                        date_field(entry, &["Date"]).unwrap_or_default(),
+                        entry
+                            .get(link_key)
+                            .or_else(|| entry.get("Link"))
+                            .and_then(|l| l.as_str())
+                            .unwrap_or("No link found"),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn category(items: &[ActivityItem], tz: &UserTimeZone) -> FavoriteCategory {
+    FavoriteCategory {
+        per_month: count_per_month(items, tz),
+        info: date_info_by_timestamp(items),
+    }
+}
@@ -0,0 +1,72 @@
+// follows.rs
+// follows.rs is in charge of :
+// - reading the follower and following lists of the "Profile" section
+// - counting follows per month, finding the oldest and newest follows and detecting mutual follows
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{count_per_month, date_field, date_info_by_timestamp, ActivityItem, DateInfo, UserTimeZone};
+
+#[derive(Serialize)]
+pub struct FollowStats {
+    pub followers: usize,
+    pub following: usize,
+    pub followers_per_month: BTreeMap<String, usize>,
+    pub following_per_month: BTreeMap<String, usize>,
+    pub follower_info: Option<DateInfo>,
+    pub following_info: Option<DateInfo>,
+    pub mutuals: Vec<String>,
+}
+
+pub fn follow_stats(data: &Value, tz: &UserTimeZone) -> FollowStats {
+    let followers = follow_list(data, "Follower List", "FansList");
+    let following = follow_list(data, "Following List", "Following");
+
+    let follower_names: HashSet<&str> = followers.iter().map(|item| item.content.as_str()).collect();
+    let mut mutuals: Vec<String> = following
+        .iter()
+        .filter(|item| follower_names.contains(item.content.as_str()))
+        .map(|item| item.content.clone())
+        .collect();
+    mutuals.sort();
+    mutuals.dedup();
+
+    FollowStats {
+        followers: followers.len(),
+        following: following.len(),
+        followers_per_month: count_per_month(&followers, tz),
+        following_per_month: count_per_month(&following, tz),
+        follower_info: date_info_by_timestamp(&followers),
+        following_info: date_info_by_timestamp(&following),
+        mutuals,
+    }
+}
+
+// Older exports keep these lists under "Your Activity" instead of "Profile"
+pub(crate) fn follow_list(data: &Value, section: &str, list_name: &str) -> Vec<ActivityItem> {
+    let list = if data["Profile"][section][list_name].is_array() {
+        &data["Profile"][section][list_name]
+    } else {
+        &data["Your Activity"][section][list_name]
+    };
+
+    list.as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|entry| {
+                    ActivityItem::new(
+                        date_field(entry, &["Date"]).unwrap_or_default(),
+                        entry
+                            .get("UserName")
+                            .or_else(|| entry.get("Username"))
+                            .and_then(|u| u.as_str())
+                            .unwrap_or(""),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
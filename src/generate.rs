@@ -0,0 +1,411 @@
+// generate.rs
+// generate.rs is in charge of :
+// - producing fake but realistic exports, to test and demo the analyzer without anyone's personal data
+// - filling every section the analyzer reads, from a number of days, contacts and a usage pattern
+// The same options and seed always give the same export
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde_json::{json, Map, Value};
+
+const FIRST_VIDEO_ID: u64 = 7_200_000_000_000_000_000;
+const FIRST_ROOM_ID: u64 = 7_300_000_000_000_000_000;
+
+const NAMES: [&str; 12] = [
+    "alex", "sam", "lea", "noah", "mia", "lucas", "emma", "hugo", "ines", "tom", "jade", "theo",
+];
+const DEVICES: [&str; 3] = ["iPhone14,5", "SM-G991B", "Pixel 7"];
+const NETWORKS: [&str; 2] = ["wifi", "4G"];
+const SHARE_METHODS: [&str; 4] = ["copy link", "chat_merge", "whatsapp", "instagram"];
+const SHARED_CONTENT: [&str; 3] = ["video", "live", "profile"];
+const MESSAGES: [&str; 6] = ["hey", "look at this", "haha", "did you see that?", "ok", "see you later"];
+const COMMENTS: [&str; 5] = ["so true", "haha", "where is this?", "love it", "first"];
+const AD_SOURCES: [&str; 4] = ["ShopApp", "NewsSite", "GameStudio", "TravelApp"];
+const AD_EVENTS: [&str; 3] = ["AddToCart", "Purchase", "Launch"];
+const AD_INTERESTS: [&str; 6] = ["Food", "Travel", "Games", "Music", "Sports", "Fashion"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UsagePattern {
+    // A few short sessions, not every day
+    Light,
+    Regular,
+    // Many long sessions every day, with a lot of liking and messaging
+    Heavy,
+}
+
+// How often things happen for a usage pattern, per day or per watched video
+struct Usage {
+    active_days: f64,
+    sessions: (u32, u32),
+    videos_per_session: (u32, u32),
+    like: f64,
+    favorite: f64,
+    share: f64,
+    comment: f64,
+    chats_per_day: (u32, u32),
+    live: f64,
+}
+
+impl UsagePattern {
+    fn usage(self) -> Usage {
+        match self {
+            UsagePattern::Light => Usage {
+                active_days: 0.5,
+                sessions: (1, 2),
+                videos_per_session: (5, 15),
+                like: 0.04,
+                favorite: 0.005,
+                share: 0.005,
+                comment: 0.003,
+                chats_per_day: (0, 1),
+                live: 0.02,
+            },
+            UsagePattern::Regular => Usage {
+                active_days: 0.85,
+                sessions: (2, 5),
+                videos_per_session: (10, 30),
+                like: 0.08,
+                favorite: 0.01,
+                share: 0.01,
+                comment: 0.005,
+                chats_per_day: (0, 3),
+                live: 0.08,
+            },
+            UsagePattern::Heavy => Usage {
+                active_days: 1.0,
+                sessions: (5, 12),
+                videos_per_session: (20, 60),
+                like: 0.15,
+                favorite: 0.02,
+                share: 0.02,
+                comment: 0.01,
+                chats_per_day: (2, 6),
+                live: 0.25,
+            },
+        }
+    }
+}
+
+pub struct GeneratorOptions {
+    pub seed: u64,
+    pub username: String,
+    // Other accounts of the export: followers, follows, chat partners and live hosts are picked among them
+    pub users: usize,
+    pub start: NaiveDate,
+    pub days: u32,
+    pub usage: UsagePattern,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions {
+            seed: 0,
+            username: String::from("demo.user"),
+            users: 40,
+            start: NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date"),
+            days: 90,
+            usage: UsagePattern::Regular,
+        }
+    }
+}
+
+// Records are added as they happen, then every list is put in the order TikTok uses
+struct Activity {
+    logins: Vec<(NaiveDateTime, Value)>,
+    watched: Vec<(NaiveDateTime, Value)>,
+    likes: Vec<(NaiveDateTime, Value)>,
+    shares: Vec<(NaiveDateTime, Value)>,
+    favorite_videos: Vec<(NaiveDateTime, Value)>,
+    comments: Vec<(NaiveDateTime, Value)>,
+    chats: Vec<Vec<(NaiveDateTime, Value)>>,
+    lives: Map<String, Value>,
+    gifts: Vec<(NaiveDateTime, Value)>,
+    ad_events: Vec<(NaiveDateTime, Value)>,
+}
+
+pub fn generate_export(options: &GeneratorOptions) -> Value {
+    let mut rng = ChaCha8Rng::seed_from_u64(options.seed);
+    let usage = options.usage.usage();
+    let users: Vec<String> = (0..options.users.max(1))
+        .map(|i| format!("{}_{}", NAMES[i % NAMES.len()], i))
+        .collect();
+    // Enough videos for some of them to be watched several times
+    let video_pool = (options.days as u64 * (usage.sessions.1 * usage.videos_per_session.1) as u64 / 2).max(100);
+    let start = options.start.and_hms_opt(0, 0, 0).expect("midnight exists");
+    let end = start + Duration::days(options.days.max(1) as i64);
+
+    let mut activity = Activity {
+        logins: Vec::new(),
+        watched: Vec::new(),
+        likes: Vec::new(),
+        shares: Vec::new(),
+        favorite_videos: Vec::new(),
+        comments: Vec::new(),
+        chats: vec![Vec::new(); users.len()],
+        lives: Map::new(),
+        gifts: Vec::new(),
+        ad_events: Vec::new(),
+    };
+
+    for day in 0..options.days as i64 {
+        let midnight = start + Duration::days(day);
+        if !rng.random_bool(usage.active_days) {
+            continue;
+        }
+        for _ in 0..rng.random_range(usage.sessions.0..=usage.sessions.1) {
+            let session = midnight + Duration::seconds(rng.random_range(7 * 3600..24 * 3600));
+            add_session(&mut rng, &usage, session, video_pool, &mut activity);
+        }
+
+        for _ in 0..rng.random_range(usage.chats_per_day.0..=usage.chats_per_day.1) {
+            // The closest contacts get most of the messages
+            let contact = skewed(&mut rng, users.len());
+            let mut date = midnight + Duration::seconds(rng.random_range(8 * 3600..23 * 3600));
+            for _ in 0..rng.random_range(1..=6) {
+                let from = if rng.random_bool(0.5) { &options.username } else { &users[contact] };
+                let content = MESSAGES[rng.random_range(0..MESSAGES.len())];
+                activity.chats[contact].push((date, json!({ "Date": stamp(date), "From": from, "Content": content })));
+                date += Duration::seconds(rng.random_range(5..600));
+            }
+        }
+
+        if rng.random_bool(usage.live) {
+            let host = &users[skewed(&mut rng, users.len())];
+            let watched = midnight + Duration::seconds(rng.random_range(18 * 3600..23 * 3600));
+            add_live(&mut rng, host, watched, &mut activity);
+        }
+
+        if rng.random_bool(0.15) {
+            let date = midnight + Duration::seconds(rng.random_range(0..24 * 3600));
+            let source = AD_SOURCES[rng.random_range(0..AD_SOURCES.len())];
+            let event = AD_EVENTS[rng.random_range(0..AD_EVENTS.len())];
+            activity
+                .ad_events
+                .push((date, json!({ "TimeStamp": stamp(date), "Source": source, "Event": event })));
+        }
+    }
+
+    let follow_list = |rng: &mut ChaCha8Rng, users: &[String], share: f64| {
+        let followed: Vec<&String> = users.iter().filter(|_| rng.random_bool(share)).collect();
+        dated_records(rng, start, end, followed.len(), |_, date, i| json!({ "Date": stamp(date), "UserName": followed[i] }))
+    };
+    let followers = follow_list(&mut rng, &users, 0.7);
+    // Following starts further in the list, so only part of the follows are mutual
+    let following = follow_list(&mut rng, &users[users.len() / 3..], 0.6);
+
+    let favorite_sounds = dated_records(&mut rng, start, end, 6, |_, date, i| {
+        json!({ "Date": stamp(date), "Link": format!("https://www.tiktok.com/music/sound-{}", i) })
+    });
+    let favorite_effects = dated_records(&mut rng, start, end, 3, |_, date, i| {
+        json!({ "Date": stamp(date), "EffectLink": format!("https://www.tiktok.com/sticker/effect-{}", i) })
+    });
+    let favorite_hashtags = dated_records(&mut rng, start, end, 4, |_, date, i| {
+        json!({ "Date": stamp(date), "Link": format!("https://www.tiktok.com/tag/tag{}", i) })
+    });
+
+    let posts = dated_records(&mut rng, start, end, (options.days / 15) as usize, |rng, date, i| {
+        json!({
+            "Date": stamp(date),
+            "Link": video_link(FIRST_VIDEO_ID + video_pool + i as u64),
+            "Likes": rng.random_range(0..500).to_string(),
+        })
+    });
+    let likes_received: u64 = posts
+        .iter()
+        .filter_map(|post| post["Likes"].as_str().and_then(|likes| likes.parse::<u64>().ok()))
+        .sum();
+
+    let coin_purchases = dated_records(&mut rng, start, end, (options.days / 30).max(1) as usize, |rng, date, _| {
+        let price = ["0.99 USD", "4.99 USD", "9.99 USD"][rng.random_range(0..3)];
+        json!({ "Date": stamp(date), "Price": price })
+    });
+    let mut orders = Map::new();
+    for (i, order) in dated_records(&mut rng, start, end, (options.days / 45) as usize, |rng, date, _| {
+        json!({ "order_date": stamp(date), "total_price": format!("{}.{:02} EUR", rng.random_range(5..60), rng.random_range(0..100)) })
+    })
+    .into_iter()
+    .enumerate()
+    {
+        orders.insert(format!("58{:010}", i), order);
+    }
+
+    let go_live = dated_records(&mut rng, start, end, (options.days / 60) as usize, |rng, date, _| {
+        let end = date + Duration::minutes(rng.random_range(10..120));
+        json!({ "StartTime": stamp(date), "EndTime": stamp(end) })
+    });
+
+    let hashtags: Vec<Value> = (0..5)
+        .map(|i| json!({ "HashtagName": format!("tag{}", i), "HashtagLink": format!("https://www.tiktok.com/tag/tag{}", i) }))
+        .collect();
+    let interests: Vec<&str> = AD_INTERESTS.iter().copied().filter(|_| rng.random_bool(0.5)).collect();
+
+    let mut chats = Map::new();
+    for (user, mut messages) in users.iter().zip(activity.chats) {
+        if !messages.is_empty() {
+            messages.sort_by_key(|(date, _)| *date);
+            chats.insert(
+                format!("Chat History with {}:", user),
+                Value::Array(messages.into_iter().map(|(_, message)| message).collect()),
+            );
+        }
+    }
+    let last_login = activity.logins.iter().map(|(date, _)| *date).max().unwrap_or(start);
+    let mut comments = activity.comments;
+    comments.sort_by_key(|(date, _)| *date);
+
+    json!({
+        "Profile": {
+            "Auto Fill": {
+                "PhoneNumber": "N/A", "Email": "N/A", "FirstName": "N/A", "LastName": "N/A", "Address": "N/A",
+                "ZipCode": "N/A", "Unit": "N/A", "City": "N/A", "State": "N/A", "Country": "N/A"
+            },
+            "Profile Info": {
+                "userName": options.username,
+                "bioDescription": "This export was generated, none of its data is real.",
+                "birthDate": "01-Jan-2000",
+                "emailAddress": "None",
+                "telephoneNumber": "None",
+                "likesReceived": likes_received.to_string(),
+            },
+            "Follower List": { "FansList": followers },
+            "Following List": { "Following": following },
+        },
+        "Your Activity": {
+            "Login History": { "LoginHistoryList": newest_first(&mut activity.logins) },
+            "Watch History": { "VideoList": newest_first(&mut activity.watched) },
+            "Like List": { "ItemFavoriteList": newest_first(&mut activity.likes) },
+            "Share History": { "ShareHistoryList": newest_first(&mut activity.shares) },
+            "Favorite Videos": { "FavoriteVideoList": newest_first(&mut activity.favorite_videos) },
+            "Favorite Sounds": { "FavoriteSoundList": favorite_sounds },
+            "Favorite Effects": { "FavoriteEffectsList": favorite_effects },
+            "Favorite Hashtags": { "FavoriteHashtagList": favorite_hashtags },
+            "Hashtag": { "HashtagList": hashtags },
+            "Purchases": {
+                "BuyGifts": { "BuyGifts": coin_purchases },
+                "SendGifts": { "SendGifts": newest_first(&mut activity.gifts) },
+            },
+            "Most Recent Location Data": { "LocationData": { "Date": stamp(last_login), "LastRegion": "FR" } },
+        },
+        "Comment": { "Comments": { "CommentsList": comments.into_iter().map(|(_, comment)| comment).collect::<Vec<Value>>() } },
+        "Direct Message": { "Direct Messages": { "ChatHistory": chats } },
+        "Post": { "Posts": { "VideoList": posts } },
+        "Tiktok Live": {
+            "Watch Live History": { "WatchLiveMap": activity.lives },
+            "Go Live History": { "GoLiveList": go_live },
+        },
+        "Tiktok Shopping": { "Order History": { "OrderHistories": orders } },
+        "Ads and data": {
+            "Off TikTok Activity": { "OffTikTokActivityDataList": newest_first(&mut activity.ad_events) },
+            "Ad Interests": { "AdInterestCategories": interests.join(" | ") },
+        },
+        "App Settings": { "Settings": { "SettingsMap": { "App Region": "FR", "App Language": "fr" } } },
+    })
+}
+
+fn add_session(rng: &mut ChaCha8Rng, usage: &Usage, session: NaiveDateTime, video_pool: u64, activity: &mut Activity) {
+    // Home, work and mobile connections
+    let ip = format!("85.12.{}.{}", rng.random_range(1..4), rng.random_range(1..4) * 10);
+    activity.logins.push((
+        session,
+        json!({
+            "Date": stamp(session),
+            "IP": ip,
+            "DeviceModel": DEVICES[rng.random_range(0..DEVICES.len())],
+            "NetworkType": NETWORKS[rng.random_range(0..NETWORKS.len())],
+            "Carrier": "Orange",
+        }),
+    ));
+
+    let mut date = session;
+    for _ in 0..rng.random_range(usage.videos_per_session.0..=usage.videos_per_session.1) {
+        // Some popular videos come back in the feed, which gives rewatches
+        let video = if rng.random_bool(0.2) {
+            skewed(rng, 100) as u64
+        } else {
+            rng.random_range(0..video_pool)
+        };
+        let link = video_link(FIRST_VIDEO_ID + video);
+        activity.watched.push((date, json!({ "Date": stamp(date), "Link": link })));
+        if rng.random_bool(usage.like) {
+            activity.likes.push((date, json!({ "date": stamp(date), "link": link })));
+        }
+        if rng.random_bool(usage.favorite) {
+            activity.favorite_videos.push((date, json!({ "Date": stamp(date), "Link": link })));
+        }
+        if rng.random_bool(usage.share) {
+            let method = SHARE_METHODS[rng.random_range(0..SHARE_METHODS.len())];
+            let content = SHARED_CONTENT[rng.random_range(0..SHARED_CONTENT.len())];
+            activity.shares.push((
+                date,
+                json!({ "Date": stamp(date), "SharedContent": content, "Link": link, "Method": method }),
+            ));
+        }
+        if rng.random_bool(usage.comment) {
+            let comment = COMMENTS[rng.random_range(0..COMMENTS.len())];
+            activity.comments.push((date, json!({ "date": stamp(date), "comment": comment })));
+        }
+        date += Duration::seconds(rng.random_range(5..60));
+    }
+}
+
+fn add_live(rng: &mut ChaCha8Rng, host: &str, watched: NaiveDateTime, activity: &mut Activity) {
+    let comments: Vec<Value> = (0..rng.random_range(0..3))
+        .map(|i| {
+            let date = watched + Duration::minutes(rng.random_range(1..30) + i);
+            json!({ "CommentTime": stamp(date), "CommentContent": COMMENTS[rng.random_range(0..COMMENTS.len())] })
+        })
+        .collect();
+    if rng.random_bool(0.3) {
+        let coins = [1, 5, 10, 99][rng.random_range(0..4)];
+        activity
+            .gifts
+            .push((watched, json!({ "Date": stamp(watched), "GiftAmount": coins.to_string(), "UserName": host })));
+    }
+    let room = FIRST_ROOM_ID + activity.lives.len() as u64;
+    activity.lives.insert(
+        room.to_string(),
+        json!({
+            "WatchTime": stamp(watched),
+            "Link": format!("https://www.tiktok.com/@{}/live", host),
+            "Comments": comments,
+        }),
+    );
+}
+
+// `count` records at random dates of the period, newest first
+fn dated_records(
+    rng: &mut ChaCha8Rng,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    count: usize,
+    record: impl Fn(&mut ChaCha8Rng, NaiveDateTime, usize) -> Value,
+) -> Vec<Value> {
+    let mut records: Vec<(NaiveDateTime, Value)> = (0..count)
+        .map(|i| {
+            let date = start + Duration::seconds(rng.random_range(0..(end - start).num_seconds()));
+            (date, record(rng, date, i))
+        })
+        .collect();
+    newest_first(&mut records)
+}
+
+// TikTok lists most sections from the newest record to the oldest
+fn newest_first(records: &mut Vec<(NaiveDateTime, Value)>) -> Vec<Value> {
+    records.sort_by_key(|(date, _)| std::cmp::Reverse(*date));
+    records.drain(..).map(|(_, record)| record).collect()
+}
+
+// An index below `len`, small indexes being much more likely
+fn skewed(rng: &mut ChaCha8Rng, len: usize) -> usize {
+    let x: f64 = rng.random();
+    ((x * x) * len as f64) as usize % len.max(1)
+}
+
+fn video_link(id: u64) -> String {
+    format!("https://www.tiktokv.com/share/video/{}/", id)
+}
+
+fn stamp(date: NaiveDateTime) -> String {
+    date.format("%Y-%m-%d %H:%M:%S").to_string()
+}
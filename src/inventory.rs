@@ -0,0 +1,158 @@
+// inventory.rs
+// inventory.rs is in charge of :
+// - walking the whole export and listing every section and subsection with its item count and date range
+// - telling which parts of the export are read by Statistics::build and which ones are ignored
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{date_utils, record_date};
+
+#[derive(Serialize)]
+pub struct InventoryEntry {
+    pub path: String,
+    pub depth: usize,
+    pub items: usize,
+    pub first_date: Option<String>,
+    pub last_date: Option<String>,
+    pub used: bool,
+}
+
+// Sections > subsections > lists are enough to describe an export, deeper levels are individual records
+const MAX_DEPTH: usize = 3;
+
+// Every path read by Statistics::build. The location summary scans the whole tree for
+// location fields and is left out, otherwise every section would count as used
+const CONSUMED_PATHS: [&str; 25] = [
+    "Profile > Profile Info",
+    "Profile > Profile Information > ProfileMap",
+    "Profile > Follower List > FansList",
+    "Profile > Following List > Following",
+    "Your Activity > Follower List > FansList",
+    "Your Activity > Following List > Following",
+    "Your Activity > Login History > LoginHistoryList",
+    "Your Activity > Watch History > VideoList",
+    "Your Activity > Like List > ItemFavoriteList",
+    "Your Activity > Share History > ShareHistoryList",
+    "Your Activity > Favorite Effects > FavoriteEffectsList",
+    "Your Activity > Favorite Hashtags > FavoriteHashtagList",
+    "Your Activity > Favorite Sounds > FavoriteSoundList",
+    "Your Activity > Favorite Videos > FavoriteVideoList",
+    "Your Activity > Hashtag > HashtagList",
+    "Your Activity > Purchases > BuyGifts > BuyGifts",
+    "Your Activity > Purchases > SendGifts > SendGifts",
+    "Comment > Comments > CommentsList",
+    "Direct Message > Direct Messages > ChatHistory",
+    "Post > Posts > VideoList",
+    "Tiktok Live > Watch Live History > WatchLiveMap",
+    "Tiktok Live > Go Live History > GoLiveList",
+    "Tiktok Shopping > Order History > OrderHistories",
+    "Ads and data > Off TikTok Activity > OffTikTokActivityDataList",
+    "Ads and data > Ad Interests > AdInterestCategories",
+];
+
+// Follow lists are read from "Profile" in recent exports and from "Your Activity" in older ones,
+// and the profile has been renamed
+const ALTERNATIVE_PATHS: [(&str, &str); 3] = [
+    ("Profile > Profile Info", "Profile > Profile Information > ProfileMap"),
+    ("Profile > Follower List > FansList", "Your Activity > Follower List > FansList"),
+    ("Profile > Following List > Following", "Your Activity > Following List > Following"),
+];
+
+// Lists the paths read by Statistics::build that this export doesn't contain
+pub fn missing_sections(data: &Value) -> Vec<&'static str> {
+    let present = |path: &str| !path.split(" > ").fold(data, |value, key| &value[key]).is_null();
+
+    CONSUMED_PATHS
+        .into_iter()
+        .filter(|path| !present(path))
+        .filter(|path| {
+            !ALTERNATIVE_PATHS
+                .iter()
+                .any(|(a, b)| (path == a && present(b)) || (path == b && present(a)))
+        })
+        .collect()
+}
+
+pub fn build_inventory(data: &Value) -> Vec<InventoryEntry> {
+    let mut entries = Vec::new();
+    walk(data, &mut Vec::new(), &mut entries);
+    entries
+}
+
+fn walk(value: &Value, path: &mut Vec<String>, entries: &mut Vec<InventoryEntry>) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for (key, child) in map {
+        path.push(key.clone());
+        let joined = path.join(" > ");
+
+        let items = match child {
+            Value::Array(list) => list.len(),
+            Value::Object(children) => children.len(),
+            _ => 1,
+        };
+        let (first, last) = date_range(child);
+
+        entries.push(InventoryEntry {
+            used: is_consumed(&joined),
+            path: joined,
+            depth: path.len() - 1,
+            items,
+            first_date: first.map(|(_, date)| date),
+            last_date: last.map(|(_, date)| date),
+        });
+        if path.len() < MAX_DEPTH {
+            walk(child, path, entries);
+        }
+        path.pop();
+    }
+}
+
+// A section is used if anything below it is read, a list is used if it is read itself or through a parent
+fn is_consumed(path: &str) -> bool {
+    CONSUMED_PATHS.iter().any(|consumed| {
+        let read_below = consumed.starts_with(path)
+            && (consumed.len() == path.len() || consumed[path.len()..].starts_with(" > "));
+        let read_above = path.starts_with(consumed) && path[consumed.len()..].starts_with(" > ");
+        read_below || read_above
+    })
+}
+
+type Dated = Option<(i64, String)>;
+
+pub(crate) fn date_range(value: &Value) -> (Dated, Dated) {
+    let mut first: Dated = None;
+    let mut last: Dated = None;
+    visit_dates(value, &mut |ts, date| {
+        if first.as_ref().is_none_or(|(first_ts, _)| ts < *first_ts) {
+            first = Some((ts, date.to_string()));
+        }
+        if last.as_ref().is_none_or(|(last_ts, _)| ts > *last_ts) {
+            last = Some((ts, date.to_string()));
+        }
+    });
+    (first, last)
+}
+
+fn visit_dates(value: &Value, on_date: &mut dyn FnMut(i64, &str)) {
+    match value {
+        Value::Object(map) => {
+            if let Some(date) = record_date(value) {
+                if let Some(ts) = date_utils::date_to_unix_timestamp(&date) {
+                    on_date(ts, &date);
+                }
+            }
+            for child in map.values() {
+                visit_dates(child, on_date);
+            }
+        }
+        Value::Array(list) => {
+            for child in list {
+                visit_dates(child, on_date);
+            }
+        }
+        _ => {}
+    }
+}
@@ -4,111 +4,333 @@
 // The library processes all of this information and nicely puts it into an instance of the struct, which is then
 // returned to main.rs
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+mod ads;
+mod anonymize;
+mod archive;
+mod charts;
+mod compare;
+mod csv;
 mod date_utils;
-
-#[derive(Clone)]
+mod diff;
+mod favorites;
+mod follows;
+mod generate;
+mod inventory;
+mod live;
+mod location;
+mod merge;
+mod purchases;
+mod rates;
+mod redact;
+mod report;
+mod shares;
+mod timeline;
+mod video_index;
+mod window;
+
+pub use ads::AdStats;
+pub use anonymize::{anonymize, pseudonym, AnonymizeOptions};
+pub use archive::{Archive, Ingested, Snapshot};
+pub use compare::{compare_accounts, AccountRow, Comparison, MetricSummary};
+pub use csv::{flatten_json, to_csv};
+pub use date_utils::UserTimeZone;
+pub use diff::{diff_exports, render_diff, CountChange, DeletedPost, ExportDiff, FieldChange, RateChange};
+pub use favorites::{FavoriteCategory, FavoriteDetails};
+pub use follows::FollowStats;
+pub use generate::{generate_export, GeneratorOptions, UsagePattern};
+pub use inventory::{build_inventory, missing_sections, InventoryEntry};
+pub use live::{HostedLive, LiveStats};
+pub use location::{LocationField, LocationStats, LocationValue};
+pub use merge::merge_exports;
+pub use purchases::PurchaseStats;
+pub use rates::{Rate, ReferenceDate};
+pub use report::{
+    all_sections, paint, render_report, report_json, select_sections, Ads, Audience, Comments, DirectMessages,
+    Favorites, Follows, Funnel, Hashtags, Likes, Live, Location, Logins, Purchases, ReportSection, Reposts, Rewatches,
+    TimeSpent, VideoConsumption,
+};
+pub use shares::ShareStats;
+pub use timeline::{build_timeline, TimelinePeriod, TimelineStep};
+pub use video_index::{video_id, EngagementFunnel, RewatchStats, RewatchedVideo};
+pub use window::AnalysisWindow;
+
+// `date` is None when the export contains a date that can't be read, `raw_date` is kept as exported for display
+#[derive(Clone, Serialize)]
 pub struct ActivityItem {
-    pub date: String,
+    pub date: Option<DateTime<Utc>>,
+    pub raw_date: String,
     pub content: String,
 }
 
+impl ActivityItem {
+    pub fn new(raw_date: impl Into<String>, content: impl Into<String>) -> ActivityItem {
+        let raw_date = raw_date.into();
+        ActivityItem {
+            date: date_utils::parse_date(&raw_date),
+            raw_date,
+            content: content.into(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct UnparsedDates {
+    pub path: String,
+    pub count: usize,
+    pub example: String,
+}
+
+#[derive(Serialize)]
 pub struct DateInfo {
     pub first: ActivityItem,
     pub last: ActivityItem,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct LoginStats {
+    pub openings: usize,
+    pub days_since_first_login: usize,
+    pub rate: Rate,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WatchStats {
+    pub videos_watched: usize,
+    pub days_since_first_video: usize,
+    pub rate: Rate,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LikeStats {
+    pub videos_liked: usize,
+    pub days_since_oldest_like: usize,
+    pub rate: Rate,
+    // Share of the watched videos that were liked
+    pub liked_percentage: f64,
+}
+
+// Number of favorite items in each category
+#[derive(Serialize, Deserialize)]
+pub struct FavoriteStats {
+    pub effects: usize,
+    pub hashtags: usize,
+    pub sounds: usize,
+    pub videos: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AudienceStats {
+    pub likes_received: usize,
+    pub videos_published: usize,
+}
+
+#[derive(Serialize)]
 pub struct Statistics {
     pub username: String,
-    pub logins: HashMap<String, usize>,
-    pub watched: HashMap<String, usize>,
+    pub logins: LoginStats,
+    pub watched: WatchStats,
     pub time: String,
-    pub favorites: HashMap<String, usize>,
-    pub likes_left: HashMap<String, usize>,
+    pub favorites: FavoriteStats,
+    pub favorite_info: FavoriteDetails,
+    pub likes_left: LikeStats,
     pub comments: usize,
     pub dms: HashMap<String, usize>,
-    pub likes_received: usize,
-    pub videos_published: usize,
-    pub shares: usize,
+    pub audience: AudienceStats,
+    pub shares: ShareStats,
     pub hashtags_viewed: usize,
     pub comment_info: Option<DateInfo>,
     pub like_info: Option<DateInfo>,
     pub watch_info: Option<DateInfo>,
     pub dm_info: Option<DateInfo>,
+    pub follows: FollowStats,
+    pub funnel: EngagementFunnel,
+    pub rewatches: RewatchStats,
+    pub live: LiveStats,
+    pub purchases: PurchaseStats,
+    pub ads: AdStats,
+    pub location: LocationStats,
+    pub period: String,
+    pub reference_date: String,
+    pub timezone: String,
+    pub unparsed_dates: Vec<UnparsedDates>,
+}
+
+// Everything that changes how the export is analyzed, set from the command line
+pub struct AnalysisOptions {
+    pub window: AnalysisWindow,
+    pub timezone: UserTimeZone,
+    pub reference: ReferenceDate,
+    // Number of decimals the averages are rounded to
+    pub decimals: usize,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> AnalysisOptions {
+        AnalysisOptions {
+            window: AnalysisWindow::default(),
+            timezone: UserTimeZone::default(),
+            reference: ReferenceDate::default(),
+            decimals: 2,
+        }
+    }
 }
 
 impl Statistics {
     pub fn build(data: Value) -> Statistics {
-        let username =
-            String::from(&data["Profile"]["Profile Info"]["userName"].to_string().replace("\"", ""));
+        Statistics::build_with(data, &AnalysisOptions::default())
+    }
+
+    // Every statistic, first/last item and chart only covers the records dated inside the window,
+    // and days and months are those of the chosen time zone
+    pub fn build_with(mut data: Value, options: &AnalysisOptions) -> Statistics {
+        let tz = &options.timezone;
+        options.window.apply(&mut data);
+
+        let username = String::from(&profile_info(&data)["userName"].to_string().replace("\"", ""));
+
+        let video_index = video_index::VideoIndex::build(&data);
 
-        let latest_timestamp = find_latest_timestamp(&data);
+        // Averages are measured up to the reference date rather than up to today
+        let reference = options.reference.resolve(&data, &options.window);
+        let rate_of = |list: &Value| rates::list_rate(list, reference, tz, options.decimals);
+        let launch_rate = rate_of(&data["Your Activity"]["Login History"]["LoginHistoryList"]);
+        let watch_rate = rate_of(&data["Your Activity"]["Watch History"]["VideoList"]);
+        let like_rate = rate_of(&data["Your Activity"]["Like List"]["ItemFavoriteList"]);
 
-        let watched_per_day = read_videos(latest_timestamp, &data)
-            .get("Watched per day")
-            .unwrap_or(&0usize)
-            .to_owned();
+        let time = daily_time(watch_rate.per_day);
 
         Statistics {
             username,
-            logins: read_logins(latest_timestamp, &data),
-            watched: read_videos(latest_timestamp, &data),
-            time: daily_time(watched_per_day),
+            logins: read_logins(launch_rate),
+            watched: read_videos(watch_rate),
+            time,
             favorites: favorites(&data),
-            likes_left: likes(latest_timestamp, &data),
+            favorite_info: favorites::favorite_details(&data, tz),
+            likes_left: likes(like_rate, &video_index.funnel()),
             comments: value_length(&data["Comment"]["Comments"]["CommentsList"]),
             dms: private_messages(&data),
-            likes_received: audience_stats(&data)
-                .get("Likes received")
-                .unwrap_or(&0usize)
-                .to_owned(),
-            videos_published: audience_stats(&data)
-                .get("Videos published")
-                .unwrap_or(&0usize)
-                .to_owned(),
-            shares: value_length(&data["Your Activity"]["Share History"]["ShareHistoryList"]),
+            audience: audience_stats(&data),
+            shares: shares::share_stats(&data, tz),
             hashtags_viewed: value_length(&data["Your Activity"]["Hashtag"]["HashtagList"]),
             comment_info: get_comment_info(&data),
             like_info: get_like_info(&data),
             watch_info: get_watch_info(&data),
             dm_info: get_dm_info(&data),
+            follows: follows::follow_stats(&data, tz),
+            funnel: video_index.funnel(),
+            rewatches: video_index.rewatches(10, tz),
+            live: live::live_stats(&data, tz),
+            purchases: purchases::purchase_stats(&data, tz),
+            ads: ads::ad_stats(&data, tz),
+            location: location::location_stats(&data),
+            period: options.window.describe(tz),
+            reference_date: tz.local_date_time(reference).format("%Y-%m-%d %H:%M:%S").to_string(),
+            timezone: tz.name(),
+            unparsed_dates: find_unparsed_dates(&data),
         }
     }
 }
 
+// Older exports keep the profile under "Profile Info", newer ones under "Profile Information > ProfileMap"
+pub fn profile_info(data: &Value) -> &Value {
+    let profile = &data["Profile"];
+    if profile["Profile Info"].is_null() {
+        &profile["Profile Information"]["ProfileMap"]
+    } else {
+        &profile["Profile Info"]
+    }
+}
+
 // Calculates how many elements there are in a JSON category
 fn value_length(input_value: &Value) -> usize {
     input_value.as_array().map(|a| a.len()).unwrap_or(0)
 }
 
-fn find_latest_timestamp(data: &Value) -> i64 {
-    let paths = vec![
-        &data["Your Activity"]["Login History"]["LoginHistoryList"],
-        &data["Your Activity"]["Watch History"]["VideoList"],
-        &data["Your Activity"]["Like List"]["ItemFavoriteList"],
-    ];
+// Returns the first non-empty string found under one of the keys, as TikTok renames fields between exports
+fn str_field<'a>(entry: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| entry.get(*key).and_then(|v| v.as_str()))
+        .filter(|value| !value.is_empty())
+}
 
-    let mut max_ts = 0;
+// Every key TikTok has used for the date of a record
+const DATE_KEYS: [&str; 8] = [
+    "Date", "date", "TimeStamp", "WatchTime", "CommentTime", "StartTime", "Start Time", "order_date",
+];
+
+// Dates are strings in most lists, but some exports store epoch seconds as numbers
+fn date_field<'a>(entry: &'a Value, keys: &[&str]) -> Option<Cow<'a, str>> {
+    keys.iter().find_map(|key| match entry.get(*key)? {
+        Value::String(date) if !date.is_empty() => Some(Cow::Borrowed(date.as_str())),
+        Value::Number(number) => Some(Cow::Owned(number.to_string())),
+        _ => None,
+    })
+}
 
-    for path in paths {
-        if let Some(list) = path.as_array() {
-            for item in list {
-                let date_str_opt = item.get("Date")
-                    .or_else(|| item.get("date"))
-                    .and_then(|d| d.as_str());
+fn record_date(record: &Value) -> Option<Cow<'_, str>> {
+    date_field(record, &DATE_KEYS)
+}
 
-                if let Some(date_str) = date_str_opt {
-                    if let Some(ts) = date_utils::date_to_unix_timestamp(date_str) {
-                        if ts > max_ts {
-                            max_ts = ts;
-                        }
-                    }
+// Lists every section holding dates that couldn't be read, instead of silently leaving them out of the statistics
+pub fn find_unparsed_dates(data: &Value) -> Vec<UnparsedDates> {
+    let mut found: BTreeMap<String, UnparsedDates> = BTreeMap::new();
+    let mut stack: Vec<(String, &Value)> = vec![(String::new(), data)];
+
+    while let Some((path, value)) = stack.pop() {
+        if let Some(date) = record_date(value) {
+            if date_utils::parse_date(&date).is_none() {
+                let entry = found.entry(path.clone()).or_insert_with(|| UnparsedDates {
+                    path: path.clone(),
+                    count: 0,
+                    example: date.to_string(),
+                });
+                entry.count += 1;
+            }
+        }
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    // Maps keyed by IDs would otherwise give one path per entry
+                    let key = if key.chars().all(|c| c.is_ascii_digit()) { "*" } else { key.as_str() };
+                    let child_path = if path.is_empty() { key.to_string() } else { format!("{} > {}", path, key) };
+                    stack.push((child_path, child));
                 }
             }
+            Value::Array(list) => stack.extend(list.iter().map(|child| (path.clone(), child))),
+            _ => {}
         }
     }
-    max_ts
+
+    found.into_values().collect()
+}
+
+// Finds the oldest and newest items by their actual date, whatever order the list is stored in
+fn date_info_by_timestamp(items: &[ActivityItem]) -> Option<DateInfo> {
+    let dated: Vec<(i64, &ActivityItem)> = items
+        .iter()
+        .filter_map(|item| item.date.map(|date| (date.timestamp(), item)))
+        .collect();
+
+    let first = dated.iter().min_by_key(|(ts, _)| *ts)?.1.clone();
+    let last = dated.iter().max_by_key(|(ts, _)| *ts)?.1.clone();
+
+    Some(DateInfo { first, last })
+}
+
+// Groups dated items into a "YYYY-MM" -> count histogram
+fn count_per_month(items: &[ActivityItem], tz: &UserTimeZone) -> BTreeMap<String, usize> {
+    let mut result = BTreeMap::new();
+    for item in items {
+        if let Some(date) = item.date {
+            *result.entry(date_utils::month_of(date.timestamp(), tz)).or_insert(0) += 1;
+        }
+    }
+    result
 }
 
 fn get_comment_info(data: &Value) -> Option<DateInfo> {
@@ -117,16 +339,16 @@ fn get_comment_info(data: &Value) -> Option<DateInfo> {
         return None;
     }
 
-    let first = ActivityItem {
-        date: list[0].get("date").and_then(|d| d.as_str()).unwrap_or("").to_string(),
-        content: list[0].get("comment").and_then(|c| c.as_str()).unwrap_or("").to_string(),
-    };
+    let first = ActivityItem::new(
+        date_field(&list[0], &["date"]).unwrap_or_default(),
+        list[0].get("comment").and_then(|c| c.as_str()).unwrap_or(""),
+    );
 
     let last_item = &list[list.len() - 1];
-    let last = ActivityItem {
-        date: last_item.get("date").and_then(|d| d.as_str()).unwrap_or("").to_string(),
-        content: last_item.get("comment").and_then(|c| c.as_str()).unwrap_or("").to_string(),
-    };
+    let last = ActivityItem::new(
+        date_field(last_item, &["date"]).unwrap_or_default(),
+        last_item.get("comment").and_then(|c| c.as_str()).unwrap_or(""),
+    );
 
     Some(DateInfo { first, last })
 }
@@ -139,16 +361,16 @@ fn get_like_info(data: &Value) -> Option<DateInfo> {
 
     // Likes seem to be sorted new to old
     let last_item = &list[list.len() - 1];
-    let first = ActivityItem {
-        date: last_item.get("date").and_then(|d| d.as_str()).unwrap_or("").to_string(),
-        content: last_item.get("link").and_then(|l| l.as_str()).unwrap_or("").to_string(),
-    };
+    let first = ActivityItem::new(
+        date_field(last_item, &["date"]).unwrap_or_default(),
+        last_item.get("link").and_then(|l| l.as_str()).unwrap_or(""),
+    );
     
     let first_item = &list[0];
-    let last = ActivityItem {
-        date: first_item.get("date").and_then(|d| d.as_str()).unwrap_or("").to_string(),
-        content: first_item.get("link").and_then(|l| l.as_str()).unwrap_or("").to_string(),
-    };
+    let last = ActivityItem::new(
+        date_field(first_item, &["date"]).unwrap_or_default(),
+        first_item.get("link").and_then(|l| l.as_str()).unwrap_or(""),
+    );
 
     Some(DateInfo { first, last })
 }
@@ -161,16 +383,16 @@ fn get_watch_info(data: &Value) -> Option<DateInfo> {
 
     // Watch history is new to old
     let last_item = &list[list.len() - 1];
-    let first = ActivityItem {
-        date: last_item.get("Date").and_then(|d| d.as_str()).unwrap_or("").to_string(),
-        content: last_item.get("Link").and_then(|l| l.as_str()).unwrap_or("No link found").to_string(),
-    };
+    let first = ActivityItem::new(
+        date_field(last_item, &["Date"]).unwrap_or_default(),
+        last_item.get("Link").and_then(|l| l.as_str()).unwrap_or("No link found"),
+    );
     
     let first_item = &list[0];
-    let last = ActivityItem {
-        date: first_item.get("Date").and_then(|d| d.as_str()).unwrap_or("").to_string(),
-        content: first_item.get("Link").and_then(|l| l.as_str()).unwrap_or("No link found").to_string(),
-    };
+    let last = ActivityItem::new(
+        date_field(first_item, &["Date"]).unwrap_or_default(),
+        first_item.get("Link").and_then(|l| l.as_str()).unwrap_or("No link found"),
+    );
 
     Some(DateInfo { first, last })
 }
@@ -187,17 +409,17 @@ fn get_dm_info(data: &Value) -> Option<DateInfo> {
     for (chat_name, messages) in chat_history {
         if let Some(messages_array) = messages.as_array() {
             for msg in messages_array {
-                if let Some(date_str) = msg.get("Date").and_then(|d| d.as_str()) {
-                    if let Some(ts) = date_utils::date_to_unix_timestamp(date_str) {
+                if let Some(date_str) = date_field(msg, &["Date"]) {
+                    if let Some(ts) = date_utils::date_to_unix_timestamp(&date_str) {
                         let content = msg.get("Content").and_then(|c| c.as_str()).unwrap_or("").to_string();
 
                         if ts < min_ts {
                             min_ts = ts;
-                            first_item = Some(ActivityItem { date: date_str.to_string(), content: format!("(in {}) {}", chat_name, content) });
+                            first_item = Some(ActivityItem::new(date_str.clone(), format!("(in {}) {}", chat_name, content)));
                         }
                         if ts > max_ts {
                             max_ts = ts;
-                            last_item = Some(ActivityItem { date: date_str.to_string(), content: format!("(in {}) {}", chat_name, content) });
+                            last_item = Some(ActivityItem::new(date_str.clone(), format!("(in {}) {}", chat_name, content)));
                         }
                     }
                 }
@@ -214,65 +436,27 @@ fn get_dm_info(data: &Value) -> Option<DateInfo> {
 
 
 // The following functions (except the test functions) calculate specific data
-fn read_logins(latest_timestamp: i64, data: &Value) -> HashMap<String, usize> {
-    let mut result: HashMap<String, usize> = HashMap::new();
-
-    let login_history = &data["Your Activity"]["Login History"]["LoginHistoryList"];
-    let login_history_len = value_length(login_history);
-
-    let days_since_1st_login = if login_history_len > 0 {
-        login_history[0]
-            .get("Date")
-            .and_then(|d| d.as_str())
-            .and_then(|s| date_utils::days_between(latest_timestamp, s))
-            .unwrap_or(0)
-    } else {
-        0
-    };
-
-    let launches_per_day = if days_since_1st_login > 0 {
-        login_history_len / days_since_1st_login
-    } else {
-        login_history_len
-    };
-
-    result.insert(String::from("Days since 1st login"), days_since_1st_login);
-    result.insert(String::from("Openings"), login_history_len);
-    result.insert(String::from("Launches per day"), launches_per_day);
-
-    result
+fn read_logins(launch_rate: Rate) -> LoginStats {
+    LoginStats {
+        openings: launch_rate.count,
+        days_since_first_login: launch_rate.days,
+        rate: launch_rate,
+    }
 }
 
-fn read_videos(latest_timestamp: i64, data: &Value) -> HashMap<String, usize> {
-    let mut result: HashMap<String, usize> = HashMap::new();
-    let watched_videos = &data["Your Activity"]["Watch History"]["VideoList"];
-    let watched_videos_len = value_length(watched_videos);
-
-    let days_since_1st_vid = if watched_videos_len > 0 {
-        watched_videos[watched_videos_len - 1]
-            .get("Date")
-            .and_then(|d| d.as_str())
-            .and_then(|s| date_utils::days_between(latest_timestamp, s))
-            .unwrap_or(0)
-    } else {
-        0
-    };
-
-    let watched_per_day = if days_since_1st_vid > 0 {
-        watched_videos_len / days_since_1st_vid
-    } else {
-        watched_videos_len
-    };
-
-    result.insert(String::from("Days since 1st video"), days_since_1st_vid);
-    result.insert(String::from("Videos watched"), watched_videos_len);
-    result.insert(String::from("Watched per day"), watched_per_day);
-
-    result
+fn read_videos(watch_rate: Rate) -> WatchStats {
+    WatchStats {
+        videos_watched: watch_rate.count,
+        days_since_first_video: watch_rate.days,
+        rate: watch_rate,
+    }
 }
 
-fn daily_time(watched_per_day: usize) -> String {
-    let total_time_in_minutes = (watched_per_day as f32 * 27.5) as usize / 60;
+// TikTok videos last 27.5 seconds on average, which turns watched videos into time spent
+pub(crate) const AVERAGE_VIDEO_SECONDS: f64 = 27.5;
+
+fn daily_time(watched_per_day: f64) -> String {
+    let total_time_in_minutes = (watched_per_day * AVERAGE_VIDEO_SECONDS) as usize / 60;
     // We are converting types for precision and readability purposes. (one after the other)
     // Originally the time is in seconds, but we transform it into minutes for simplicity (that's the / 60).
     let hours = total_time_in_minutes / 60;
@@ -282,83 +466,25 @@ fn daily_time(watched_per_day: usize) -> String {
     result
 }
 
-fn favorites(data: &Value) -> HashMap<String, usize> {
-    let mut result = HashMap::new();
-
-    let effects_len = value_length(&data["Your Activity"]["Favorite Effects"]["FavoriteEffectsList"]);
-
-    let hashtags_len = value_length(&data["Your Activity"]["Favorite Hashtags"]["FavoriteHashtagList"]);
-
-    let sounds_len = value_length(&data["Your Activity"]["Favorite Sounds"]["FavoriteSoundList"]);
+fn favorites(data: &Value) -> FavoriteStats {
+    let activity = &data["Your Activity"];
 
-    let videos_len = value_length(&data["Your Activity"]["Favorite Videos"]["FavoriteVideoList"]);
-
-    result.insert(String::from("Effects"), effects_len);
-    result.insert(String::from("Hashtags"), hashtags_len);
-    result.insert(String::from("Sounds"), sounds_len);
-    result.insert(String::from("Videos"), videos_len);
-
-    result
+    FavoriteStats {
+        effects: value_length(&activity["Favorite Effects"]["FavoriteEffectsList"]),
+        hashtags: value_length(&activity["Favorite Hashtags"]["FavoriteHashtagList"]),
+        sounds: value_length(&activity["Favorite Sounds"]["FavoriteSoundList"]),
+        videos: value_length(&activity["Favorite Videos"]["FavoriteVideoList"]),
+    }
 }
 
-fn likes(latest_timestamp: i64, data: &Value) -> HashMap<String, usize> {
-    let mut result: HashMap<String, usize> = HashMap::new();
-
-    let watched_videos = &data["Your Activity"]["Watch History"]["VideoList"];
-    let watched_videos_len = value_length(watched_videos);
-    let days_since_1st_vid = if watched_videos_len > 0 {
-        watched_videos[watched_videos_len - 1]
-            .get("Date")
-            .and_then(|d| d.as_str())
-            .and_then(|s| date_utils::days_between(latest_timestamp, s))
-            .unwrap_or(0)
-    } else {
-        0
-    };
-
-    let watched_per_day_float = if days_since_1st_vid > 0 {
-        watched_videos_len as f64 / days_since_1st_vid as f64
-    } else {
-        watched_videos_len as f64
-    };
-
-    let liked_videos = &data["Your Activity"]["Like List"]["ItemFavoriteList"];
-    let liked_videos_len = value_length(liked_videos);
-
-    let days_since_oldest_like = if liked_videos_len > 0 {
-        liked_videos[liked_videos_len - 1]
-            .get("date")
-            .and_then(|d| d.as_str())
-            .and_then(|s| date_utils::days_between(latest_timestamp, s))
-            .unwrap_or(0)
-    } else {
-        0
-    };
-
-    let likes_per_day = if days_since_oldest_like > 0 {
-        liked_videos_len / days_since_oldest_like
-    } else {
-        liked_videos_len
-    };
-
-    let liked_percentage = if watched_per_day_float > 0.0 {
-        ((likes_per_day as f64 / watched_per_day_float) * 100.0) as usize
-    } else {
-        0
-    };
-
-    result.insert(String::from("Videos liked"), liked_videos_len);
-    result.insert(
-        String::from("Days since oldest like"),
-        days_since_oldest_like,
-    );
-    result.insert(String::from("Likes per day"), likes_per_day);
-    result.insert(
-        String::from("Liked videos percentage"),
-        liked_percentage,
-    );
-
-    result
+fn likes(like_rate: Rate, funnel: &EngagementFunnel) -> LikeStats {
+    LikeStats {
+        videos_liked: like_rate.count,
+        days_since_oldest_like: like_rate.days,
+        rate: like_rate,
+        // Based on the videos that appear both in the watch history and in the like list
+        liked_percentage: funnel.liked_percentage,
+    }
 }
 
 fn private_messages(data: &Value) -> HashMap<String, usize> {
@@ -385,31 +511,41 @@ fn private_messages(data: &Value) -> HashMap<String, usize> {
     result
 }
 
-fn audience_stats(data: &Value) -> HashMap<String, usize> {
-    let mut result: HashMap<String, usize> = HashMap::new();
-
-    let videos_published = value_length(&data["Post"]["Posts"]["VideoList"]);
-
-    let likes_u64 = data
-        .get("Profile")
-        .and_then(|p| p.get("Profile Info"))
-        .and_then(|pi| pi.get("likesReceived"))
+fn audience_stats(data: &Value) -> AudienceStats {
+    let likes_received = profile_info(data)
+        .get("likesReceived")
         .and_then(|lr| lr.as_str())
-        .and_then(|s| s.parse::<u64>().ok())
+        .and_then(|s| s.parse::<usize>().ok())
         .unwrap_or(0);
 
-    result.insert(String::from("Videos published"), videos_published);
-    result.insert(String::from("Likes received"), likes_u64 as usize);
-
-    result
+    AudienceStats {
+        likes_received,
+        videos_published: value_length(&data["Post"]["Posts"]["VideoList"]),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use core::panic;
 
+    mod golden;
     mod tests_read_file;
 
+    use super::{AnalysisOptions, AnalysisWindow, ReferenceDate, UserTimeZone};
+
+    // Tests run in UTC so that they don't depend on the zone of the machine running them
+    fn utc() -> UserTimeZone {
+        UserTimeZone::Named(chrono_tz::UTC)
+    }
+
+    fn options(window: AnalysisWindow) -> AnalysisOptions {
+        AnalysisOptions {
+            window,
+            timezone: utc(),
+            ..AnalysisOptions::default()
+        }
+    }
+
     // These functions test what happens in different cases by using files that I made
     // in order to check if the file is readable and valid
 
@@ -452,9 +588,1005 @@ mod tests {
             eprintln!("Error while trying to read string: {err}");
             panic!("Error while trying to read string: {err}")
         });
-        let username = &data["Profile"]["Profile Info"]["userName"];
+        let username = &super::profile_info(&data)["userName"];
 
         assert_eq!(username.as_str(), Some("john.doe"));
+        assert_eq!(crate::Statistics::build(data).username, "john.doe");
         // Same as for function file_found_but_not_valid, but here the value exists
     }
+
+    #[test]
+    fn follow_stats_detect_mutuals_and_months() {
+        let data = serde_json::json!({
+            "Profile": {
+                "Follower List": { "FansList": [
+                    { "Date": "2024-02-10 10:00:00", "UserName": "alice" },
+                    { "Date": "2023-12-01 08:30:00", "UserName": "bob" }
+                ]},
+                "Following List": { "Following": [
+                    { "Date": "2024-01-05 12:00:00", "UserName": "alice" },
+                    { "Date": "2024-01-20 12:00:00", "UserName": "carol" }
+                ]}
+            }
+        });
+        let follows = super::follows::follow_stats(&data, &utc());
+
+        assert_eq!(follows.followers, 2);
+        assert_eq!(follows.following, 2);
+        assert_eq!(follows.mutuals, vec![String::from("alice")]);
+        assert_eq!(follows.followers_per_month.get("2023-12"), Some(&1));
+        assert_eq!(follows.following_per_month.get("2024-01"), Some(&2));
+
+        let info = follows.follower_info.unwrap();
+        assert_eq!(info.first.content, "bob");
+        assert_eq!(info.last.content, "alice");
+    }
+
+    #[test]
+    fn share_stats_group_channels_and_content() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Share History": { "ShareHistoryList": [
+                    { "Date": "2024-03-02 10:00:00", "SharedContent": "video", "Link": "https://www.tiktokv.com/share/video/1/", "Method": "chat_head" },
+                    { "Date": "2024-03-01 09:00:00", "SharedContent": "video", "Link": "https://www.tiktokv.com/share/video/2/", "Method": "copy" },
+                    { "Date": "2024-02-01 09:00:00", "SharedContent": "live", "Link": "", "Method": "whatsapp" }
+                ]},
+                "Watch History": { "VideoList": [
+                    { "Date": "2024-03-02 09:00:00", "Link": "a" },
+                    { "Date": "2024-03-01 09:00:00", "Link": "b" },
+                    { "Date": "2024-02-01 09:00:00", "Link": "c" },
+                    { "Date": "2024-01-01 09:00:00", "Link": "d" }
+                ]}
+            }
+        });
+        let shares = super::shares::share_stats(&data, &utc());
+
+        assert_eq!(shares.total, 3);
+        assert_eq!(shares.per_month.get("2024-03"), Some(&2));
+        assert_eq!(shares.by_method.get("Direct message"), Some(&1));
+        assert_eq!(shares.by_method.get("Copy link"), Some(&1));
+        assert_eq!(shares.by_content.get("video"), Some(&2));
+        assert_eq!(shares.shares_per_watched_video, 0.75);
+        assert_eq!(shares.share_info.unwrap().first.raw_date, "2024-02-01 09:00:00");
+    }
+
+    #[test]
+    fn favorite_details_match_liked_videos() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Favorite Videos": { "FavoriteVideoList": [
+                    { "Date": "2024-01-10 10:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" },
+                    { "Date": "2024-02-10 10:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000002/" }
+                ]},
+                "Favorite Effects": { "FavoriteEffectsList": [
+                    { "Date": "2024-02-11 10:00:00", "EffectLink": "https://effect/1" }
+                ]},
+                "Like List": { "ItemFavoriteList": [
+                    { "date": "2024-01-09 10:00:00", "link": "https://www.tiktok.com/@a/video/7000000000000000001" }
+                ]}
+            }
+        });
+        let favorites = super::favorites::favorite_details(&data, &utc());
+
+        assert_eq!(favorites.per_month.get("2024-02"), Some(&2));
+        assert_eq!(favorites.videos_also_liked, 1);
+        assert_eq!(favorites.videos_also_liked_percentage, 50.0);
+        assert_eq!(favorites.effects.info.unwrap().last.content, "https://effect/1");
+        assert!(favorites.sounds.info.is_none());
+    }
+
+    #[test]
+    fn video_ids_join_watch_history_with_likes() {
+        assert_eq!(
+            super::video_id("https://www.tiktokv.com/share/video/7212345678901234567/"),
+            Some(String::from("7212345678901234567"))
+        );
+        assert_eq!(
+            super::video_id("https://www.tiktok.com/@someone/video/7212345678901234567?lang=en"),
+            Some(String::from("7212345678901234567"))
+        );
+        assert_eq!(super::video_id("https://vm.tiktok.com/ZMabcdef/"), None);
+
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Watch History": { "VideoList": [
+                    { "Date": "2024-03-02 09:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" },
+                    { "Date": "2024-03-01 09:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000002/" },
+                    { "Date": "2024-03-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" }
+                ]},
+                "Like List": { "ItemFavoriteList": [
+                    { "date": "2024-03-02 09:01:00", "link": "https://www.tiktok.com/@a/video/7000000000000000001" },
+                    { "date": "2024-02-01 09:01:00", "link": "https://www.tiktok.com/@a/video/7000000000000000009" }
+                ]},
+                "Share History": { "ShareHistoryList": [
+                    { "Date": "2024-03-01 09:02:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000002/" }
+                ]}
+            }
+        });
+        let funnel = super::video_index::VideoIndex::build(&data).funnel();
+
+        assert_eq!(funnel.watched, 2);
+        assert_eq!(funnel.liked, 1);
+        assert_eq!(funnel.favorited, 0);
+        assert_eq!(funnel.shared, 1);
+        assert_eq!(funnel.liked_percentage, 50.0);
+    }
+
+    #[test]
+    fn rewatches_count_views_per_video() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Watch History": { "VideoList": [
+                    { "Date": "2024-03-05 09:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" },
+                    { "Date": "2024-03-02 09:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000002/" },
+                    { "Date": "2024-03-02 08:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" },
+                    { "Date": "2024-03-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" },
+                    { "Date": "2024-03-01 07:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000002/" },
+                    { "Date": "2024-03-01 06:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000003/" }
+                ]}
+            }
+        });
+        let rewatches = super::video_index::VideoIndex::build(&data).rewatches(1, &utc());
+
+        assert_eq!(rewatches.rewatched_videos, 2);
+        assert_eq!(rewatches.extra_views, 3);
+        assert_eq!(rewatches.top.len(), 1);
+        assert_eq!(rewatches.top[0].video_id, "7000000000000000001");
+        assert_eq!(rewatches.top[0].views, 3);
+        assert_eq!(rewatches.top[0].first_watched, "2024-03-01 08:00:00");
+        assert_eq!(rewatches.top[0].span_days, 4);
+    }
+
+    #[test]
+    fn live_stats_read_hosts_comments_and_durations() {
+        let data = serde_json::json!({
+            "Tiktok Live": {
+                "Watch Live History": { "WatchLiveMap": {
+                    "7300000000000000001": {
+                        "WatchTime": "2024-01-10 20:00:00",
+                        "Link": "https://www.tiktok.com/@streamer/live",
+                        "Comments": [
+                            { "CommentTime": "2024-01-10 20:05:00", "CommentContent": "hello" }
+                        ]
+                    },
+                    "7300000000000000002": {
+                        "WatchTime": "2024-02-10 20:00:00",
+                        "Link": "https://www.tiktok.com/@streamer/live"
+                    }
+                }},
+                "Go Live History": { "GoLiveList": [
+                    { "StartTime": "2024-03-01 18:00:00", "EndTime": "2024-03-01 18:45:00" },
+                    { "StartTime": "2024-03-02 18:00:00", "Duration": "01:10:00" }
+                ]}
+            }
+        });
+        let live = super::live::live_stats(&data, &utc());
+
+        assert_eq!(live.lives_watched, 2);
+        assert_eq!(live.hosts.get("streamer"), Some(&2));
+        assert_eq!(live.comments, 1);
+        assert_eq!(live.comment_info.unwrap().first.content, "(in streamer's live) hello");
+        assert_eq!(live.hosted.len(), 2);
+        assert_eq!(live.hosted_minutes, 115);
+    }
+
+    #[test]
+    fn purchase_stats_add_up_spend_per_currency() {
+        let data = serde_json::json!({
+            "Your Activity": { "Purchases": {
+                "BuyGifts": { "BuyGifts": [
+                    { "Date": "2024-01-10 10:00:00", "Price": "0.99 USD" },
+                    { "Date": "2024-01-20 10:00:00", "Price": "$1,234.50" }
+                ]},
+                "SendGifts": { "SendGifts": [
+                    { "Date": "2024-01-11 10:00:00", "GiftAmount": "10", "UserName": "streamer" },
+                    { "Date": "2024-01-12 10:00:00", "GiftAmount": 5, "UserName": "streamer" }
+                ]}
+            }},
+            "Tiktok Shopping": { "Order History": { "OrderHistories": {
+                "1": { "order_date": "2024-02-01 10:00:00", "total_price": "12,50 €" }
+            }}}
+        });
+        let purchases = super::purchases::purchase_stats(&data, &utc());
+
+        assert_eq!(purchases.coin_purchases, 2);
+        assert_eq!(purchases.orders, 1);
+        assert_eq!(purchases.spend_by_currency.get("USD"), Some(&1235.49));
+        assert_eq!(purchases.spend_by_currency.get("EUR"), Some(&12.5));
+        assert_eq!(purchases.spend_per_month["2024-02"].get("EUR"), Some(&12.5));
+        assert_eq!(purchases.gift_coins_sent, 15);
+        assert_eq!(purchases.gift_recipients.get("streamer"), Some(&15));
+    }
+
+    #[test]
+    fn ad_stats_count_sources_and_interests() {
+        let data = serde_json::json!({
+            "Ads and data": {
+                "Off TikTok Activity": { "OffTikTokActivityDataList": [
+                    { "TimeStamp": "2024-01-10 10:00:00", "Source": "Shop App", "Event": "Purchase" },
+                    { "TimeStamp": "2024-02-10 10:00:00", "Source": "Shop App", "Event": "AddToCart" },
+                    { "TimeStamp": "2024-02-11 10:00:00", "Source": "News Site", "Event": "Purchase" }
+                ]},
+                "Ad Interests": { "AdInterestCategories": "Travel | Food | Travel" }
+            }
+        });
+        let ads = super::ads::ad_stats(&data, &utc());
+
+        assert_eq!(ads.off_tiktok_events, 3);
+        assert_eq!(ads.sources.get("Shop App"), Some(&2));
+        assert_eq!(ads.event_types.get("Purchase"), Some(&2));
+        assert_eq!(ads.events_per_month.get("2024-02"), Some(&2));
+        assert_eq!(ads.ad_interests, vec![String::from("Food"), String::from("Travel")]);
+    }
+
+    #[test]
+    fn location_stats_collect_distinct_values() {
+        let data = serde_json::json!({
+            "Profile": { "Auto Fill": { "Country": "France", "ZipCode": "N/A" } },
+            "Your Activity": {
+                "Login History": { "LoginHistoryList": [
+                    { "Date": "2024-03-01 10:00:00", "IP": "85.1.2.3" },
+                    { "Date": "2024-01-01 10:00:00", "IP": "85.1.2.3" },
+                    { "Date": "2024-02-01 10:00:00", "IP": "10.0.0.1" }
+                ]}
+            }
+        });
+        let location = super::location::location_stats(&data);
+
+        assert_eq!(location.fields.len(), 2);
+        let country = &location.fields[0];
+        assert_eq!(country.path, "Profile > Auto Fill > Country");
+        assert!(!country.flagged);
+
+        let ips = &location.fields[1];
+        assert_eq!(ips.path, "Your Activity > Login History > LoginHistoryList > IP");
+        assert!(ips.flagged);
+        assert_eq!(ips.values[0].value, "85.1.2.3");
+        assert_eq!(ips.values[0].occurrences, 2);
+        assert_eq!(ips.values[0].first_seen.as_deref(), Some("2024-01-01 10:00:00"));
+        assert_eq!(ips.values[0].last_seen.as_deref(), Some("2024-03-01 10:00:00"));
+    }
+
+    #[test]
+    fn inventory_marks_used_and_ignored_sections() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Watch History": { "VideoList": [
+                    { "Date": "2024-03-02 09:00:00", "Link": "a" },
+                    { "Date": "2024-01-02 09:00:00", "Link": "b" }
+                ]},
+                "Searches": { "SearchList": [
+                    { "Date": "2024-02-02 09:00:00", "SearchTerm": "cats" }
+                ]}
+            }
+        });
+        let inventory = super::build_inventory(&data);
+        let entry = |path: &str| inventory.iter().find(|entry| entry.path == path).unwrap();
+
+        assert!(entry("Your Activity").used);
+        let watch_list = entry("Your Activity > Watch History > VideoList");
+        assert!(watch_list.used);
+        assert_eq!(watch_list.depth, 2);
+        assert_eq!(watch_list.items, 2);
+        assert_eq!(watch_list.first_date.as_deref(), Some("2024-01-02 09:00:00"));
+        assert_eq!(watch_list.last_date.as_deref(), Some("2024-03-02 09:00:00"));
+        assert!(!entry("Your Activity > Searches").used);
+        assert!(!entry("Your Activity > Searches > SearchList").used);
+    }
+
+    #[test]
+    fn analysis_window_keeps_only_the_chosen_period() {
+        let data = serde_json::json!({
+            "Profile": { "Profile Info": { "userName": "john.doe" } },
+            "Your Activity": {
+                "Watch History": { "VideoList": [
+                    { "Date": "2024-03-02 09:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000003/" },
+                    { "Date": "2024-01-15 09:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000002/" },
+                    { "Date": "2023-12-31 23:00:00", "Link": "https://www.tiktokv.com/share/video/7000000000000000001/" }
+                ]}
+            }
+        });
+
+        let year = super::AnalysisWindow::parse(Some("2024"), Some("2024"), &data, &utc()).unwrap();
+        let statistics = super::Statistics::build_with(data.clone(), &options(year));
+        assert_eq!(statistics.watched.videos_watched, 2);
+        assert_eq!(statistics.watch_info.unwrap().first.raw_date, "2024-01-15 09:00:00");
+
+        let last_month = super::AnalysisWindow::parse(Some("30d"), None, &data, &utc()).unwrap();
+        let statistics = super::Statistics::build_with(data.clone(), &options(last_month));
+        assert_eq!(statistics.watched.videos_watched, 1);
+
+        let january = super::AnalysisWindow::parse(None, Some("2024-01"), &data, &utc()).unwrap();
+        assert!(january.contains(1706745599)); // 2024-01-31 23:59:59
+        assert!(!january.contains(1706745600));
+
+        assert!(super::AnalysisWindow::parse(Some("yesterday"), None, &data, &utc()).is_err());
+        assert!(super::AnalysisWindow::parse(Some("2024"), Some("2023"), &data, &utc()).is_err());
+        // Malformed or out of range durations are errors, not panics
+        for since in ["3\u{e9}", "400000000y", "4000000000d"] {
+            assert!(super::AnalysisWindow::parse(Some(since), None, &data, &utc()).is_err(), "{}", since);
+        }
+    }
+
+    #[test]
+    fn time_zone_moves_days_and_months() {
+        let paris = UserTimeZone::parse("Europe/Paris").unwrap();
+        let new_york = UserTimeZone::parse("-05:00").unwrap();
+        assert_eq!(UserTimeZone::parse("+0530").unwrap().name(), "UTC+05:30");
+        assert!(UserTimeZone::parse("Mars/Olympus").is_err());
+
+        // 23:30 UTC on January 31st is already February in Paris
+        assert_eq!(super::date_utils::month_key("2024-01-31 23:30:00", &utc()).as_deref(), Some("2024-01"));
+        assert_eq!(super::date_utils::month_key("2024-01-31 23:30:00", &paris).as_deref(), Some("2024-02"));
+
+        // 03:00 UTC is still the previous evening in New York
+        let reference = super::date_utils::date_to_unix_timestamp("2024-03-02 03:00:00").unwrap();
+        assert_eq!(super::date_utils::days_between(reference, "2024-03-01 22:00:00", &utc()), Some(1));
+        assert_eq!(super::date_utils::days_between(reference, "2024-03-01 22:00:00", &new_york), Some(0));
+
+        // Bounds typed by the user are read in their zone
+        let data = serde_json::json!({});
+        let window = AnalysisWindow::parse(Some("2024-03-01"), None, &data, &paris).unwrap();
+        assert_eq!(window.since, super::date_utils::date_to_unix_timestamp("2024-02-29 23:00:00"));
+    }
+
+    #[test]
+    fn dates_are_parsed_and_unreadable_ones_reported() {
+        let iso = super::ActivityItem::new("2024-03-01T10:00:00Z", "");
+        let epoch = super::ActivityItem::new("1709287200", "");
+        let tiktok = super::ActivityItem::new("2024-03-01 10:00:00", "");
+        assert!(iso.date.is_some());
+        assert_eq!(iso.date, epoch.date);
+        assert_eq!(iso.date, tiktok.date);
+        assert_eq!(tiktok.raw_date, "2024-03-01 10:00:00");
+        assert!(super::ActivityItem::new("yesterday", "").date.is_none());
+
+        let data = serde_json::json!({
+            "Profile": { "Profile Info": { "userName": "john.doe" } },
+            "Your Activity": {
+                "Watch History": { "VideoList": [
+                    { "Date": 1709287200, "Link": "a" },
+                    { "Date": "01/03/2024", "Link": "b" },
+                    { "Date": "02/03/2024", "Link": "c" }
+                ]}
+            }
+        });
+        let statistics = super::Statistics::build_with(data, &options(AnalysisWindow::default()));
+        assert_eq!(statistics.watch_info.unwrap().last.raw_date, "1709287200");
+        assert_eq!(statistics.unparsed_dates.len(), 1);
+        assert_eq!(statistics.unparsed_dates[0].path, "Your Activity > Watch History > VideoList");
+        assert_eq!(statistics.unparsed_dates[0].count, 2);
+    }
+
+    #[test]
+    fn rates_are_fractional_and_measured_up_to_the_reference() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Login History": { "LoginHistoryList": [
+                    { "Date": "2024-03-10 08:00:00" },
+                    { "Date": "2024-03-01 08:00:00" },
+                    { "Date": "2024-03-04 08:00:00" },
+                    { "Date": "2024-03-05 08:00:00" }
+                ]},
+                "Watch History": { "VideoList": [
+                    { "Date": "2024-03-10 08:00:00", "Link": "https://www.tiktokv.com/share/video/1/" }
+                ]}
+            }
+        });
+
+        let latest = crate::Statistics::build_with(data.clone(), &options(AnalysisWindow::default()));
+        // The oldest login is found by date, not by its place in the list
+        assert_eq!(latest.logins.rate.from.as_deref(), Some("2024-03-01"));
+        assert_eq!(latest.logins.rate.to, "2024-03-10");
+        assert_eq!(latest.logins.rate.days, 9);
+        assert_eq!(latest.logins.rate.per_day, 0.44);
+        // A single day still gives its number of events
+        assert_eq!(latest.watched.rate.per_day, 1.0);
+
+        let fixed = AnalysisOptions {
+            reference: ReferenceDate::parse("2024-03-21", &utc()).unwrap(),
+            decimals: 3,
+            ..options(AnalysisWindow::default())
+        };
+        let statistics = crate::Statistics::build_with(data, &fixed);
+        assert_eq!(statistics.logins.rate.days, 20);
+        assert_eq!(statistics.logins.rate.per_day, 0.2);
+        assert_eq!(statistics.reference_date, "2024-03-21 23:59:59");
+        assert_eq!(statistics.likes_left.rate.from, None);
+    }
+
+    #[test]
+    fn export_reference_is_read_from_the_export() {
+        let data = serde_json::json!({
+            "Your Activity": { "Login History": { "LoginHistoryList": [
+                { "Date": "2024-03-10 08:00:00" },
+                { "Date": "2024-03-01 08:00:00" }
+            ]}}
+        });
+        // Neither the file nor its modification time are needed when the export holds dates
+        let missing = std::path::Path::new("missing_export.json");
+        assert_eq!(ReferenceDate::export_date(&data, missing), Ok(ReferenceDate::ExportDate(1710057600)));
+
+        let empty = serde_json::json!({});
+        assert!(ReferenceDate::export_date(&empty, missing).is_err());
+        // An archive directory has no date of its own
+        assert!(ReferenceDate::export_date(&empty, &std::env::temp_dir()).is_err());
+    }
+
+    #[test]
+    fn rates_leave_out_records_after_the_reference() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Login History": { "LoginHistoryList": [
+                    { "Date": "2024-03-10 08:00:00" },
+                    { "Date": "2024-03-05 08:00:00" },
+                    { "Date": "2024-03-01 08:00:00" }
+                ]}
+            }
+        });
+        let reference = |date: &str| AnalysisOptions {
+            reference: ReferenceDate::parse(date, &utc()).unwrap(),
+            ..options(AnalysisWindow::default())
+        };
+
+        let statistics = crate::Statistics::build_with(data.clone(), &reference("2024-03-05"));
+        assert_eq!(statistics.logins.openings, 2);
+        assert_eq!(statistics.logins.rate.days, 4);
+        assert_eq!(statistics.logins.rate.per_day, 0.5);
+
+        // Nothing happened yet on a reference before the data
+        let statistics = crate::Statistics::build_with(data, &reference("2024-02-01"));
+        assert_eq!(statistics.logins.rate.count, 0);
+        assert_eq!(statistics.logins.rate.days, 0);
+        assert_eq!(statistics.logins.rate.per_day, 0.0);
+        assert_eq!(statistics.logins.rate.from, None);
+    }
+
+    #[test]
+    fn typed_stats_round_trip_through_json() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Login History": { "LoginHistoryList": [{ "Date": "2024-03-01 08:00:00" }] },
+                "Favorite Sounds": { "FavoriteSoundList": [{ "Date": "2024-03-01 08:00:00", "Link": "sound" }] }
+            }
+        });
+        let statistics = crate::Statistics::build_with(data, &options(AnalysisWindow::default()));
+        let json = serde_json::to_value(&statistics).unwrap();
+
+        let logins: crate::LoginStats = serde_json::from_value(json["logins"].clone()).unwrap();
+        assert_eq!(logins.openings, 1);
+        assert_eq!(logins.rate.per_day, 1.0);
+        let favorites: crate::FavoriteStats = serde_json::from_value(json["favorites"].clone()).unwrap();
+        assert_eq!((favorites.sounds, favorites.videos), (1, 0));
+        let likes: crate::LikeStats = serde_json::from_value(json["likes_left"].clone()).unwrap();
+        assert_eq!(likes.videos_liked, 0);
+    }
+
+    #[test]
+    fn timeline_counts_records_per_subsection_and_period() {
+        let data = serde_json::json!({
+            "Your Activity": {
+                "Login History": { "LoginHistoryList": [
+                    { "Date": "2024-03-01 08:00:00" },
+                    { "Date": "2024-04-02 08:00:00" }
+                ]},
+                "Like List": { "ItemFavoriteList": [{ "date": "2024-03-05 10:00:00", "link": "x" }] }
+            },
+            "Profile": { "Profile Info": { "userName": "john.doe" } }
+        });
+
+        let timeline = crate::build_timeline(&data, &utc(), crate::TimelineStep::Month);
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].period, "2024-03");
+        assert_eq!(timeline[0].total, 2);
+        assert_eq!(timeline[0].sections.get("Like List"), Some(&1));
+        assert_eq!(timeline[1].sections.get("Login History"), Some(&1));
+
+        let missing = crate::missing_sections(&data);
+        assert!(missing.contains(&"Your Activity > Watch History > VideoList"));
+        assert!(!missing.contains(&"Your Activity > Login History > LoginHistoryList"));
+    }
+
+    #[test]
+    fn csv_flattens_json_and_quotes_fields() {
+        let value = serde_json::json!({ "logins": { "openings": 3 }, "top": ["a,b"], "info": null });
+        let rows: Vec<Vec<String>> = crate::flatten_json(&value)
+            .into_iter()
+            .map(|(field, value)| vec![field, value])
+            .collect();
+        assert_eq!(
+            crate::to_csv(&["field", "value"], &rows),
+            "field,value\ninfo,\nlogins.openings,3\ntop.0,\"a,b\"\n"
+        );
+    }
+
+    #[test]
+    fn sections_can_be_picked_removed_and_reordered() {
+        let names = |spec: &str| -> Vec<&'static str> {
+            let spec: Vec<String> = spec.split(',').map(String::from).collect();
+            crate::select_sections(&spec)
+                .unwrap()
+                .iter()
+                .map(|section| section.name())
+                .collect()
+        };
+
+        assert_eq!(names("dms,logins"), vec!["dms", "logins"]);
+        assert_eq!(names("-location").len(), crate::all_sections().len() - 1);
+        assert!(!names("-location").contains(&"location"));
+        let moved = names("audience,...,-ads");
+        assert_eq!(moved.first(), Some(&"audience"));
+        assert_eq!(moved.len(), crate::all_sections().len() - 1);
+        assert!(crate::select_sections(&[String::from("nope")]).is_err());
+    }
+
+    #[test]
+    fn report_is_rendered_to_a_string() {
+        let data = serde_json::json!({
+            "Profile": { "Profile Info": { "userName": "john.doe" } },
+            "Direct Message": { "Direct Messages": { "ChatHistory": {
+                "Chat History with bob:": [{ "Date": "2024-03-01 08:00:00", "From": "bob", "Content": "hi" }],
+                "Chat History with alice:": [
+                    { "Date": "2024-03-02 08:00:00", "From": "alice", "Content": "hey" },
+                    { "Date": "2024-03-03 08:00:00", "From": "john.doe", "Content": "hello" }
+                ]
+            }}}
+        });
+        let statistics = crate::Statistics::build_with(data, &options(AnalysisWindow::default()));
+        let sections = crate::select_sections(&[String::from("dms")]).unwrap();
+
+        let report = crate::render_report(&statistics, &sections, false);
+        assert!(report.starts_with("The data of john.doe has been analyzed"));
+        assert!(report.contains("---------- DIRECT MESSAGES"));
+        assert!(!report.contains("LOGINS"));
+        assert!(!report.contains('\x1b'));
+        // The biggest conversation comes first
+        assert!(report.find("alice").unwrap() < report.find("bob").unwrap());
+        assert_eq!(report, crate::render_report(&statistics, &sections, false));
+
+        // With every section, the JSON report holds every statistic
+        let everything = crate::report_json(&statistics, &crate::all_sections());
+        assert_eq!(everything, serde_json::to_value(&statistics).unwrap());
+        assert!(crate::report_json(&statistics, &sections).get("logins").is_none());
+    }
+
+    #[test]
+    fn diff_reports_what_changed_between_two_exports() {
+        let old = serde_json::json!({
+            "Profile": {
+                "Profile Info": { "userName": "john.doe", "bioDescription": "old bio" },
+                "Follower List": { "FansList": [
+                    { "Date": "2024-01-01 08:00:00", "UserName": "alice" },
+                    { "Date": "2024-01-02 08:00:00", "UserName": "bob" }
+                ]}
+            },
+            "Your Activity": { "Watch History": { "VideoList": [
+                { "Date": "2024-03-02 08:00:00", "Link": "https://www.tiktokv.com/share/video/1/" },
+                { "Date": "2024-03-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/2/" }
+            ]}},
+            "Post": { "Posts": { "VideoList": [
+                { "Date": "2024-02-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/10/" },
+                { "Date": "2024-02-05 08:00:00", "Link": "https://www.tiktokv.com/share/video/11/" }
+            ]}}
+        });
+        let new = serde_json::json!({
+            "Profile": {
+                "Profile Info": { "userName": "john.doe", "bioDescription": "new bio" },
+                "Follower List": { "FansList": [
+                    { "Date": "2024-01-02 08:00:00", "UserName": "bob" },
+                    { "Date": "2024-03-03 08:00:00", "UserName": "carol" }
+                ]}
+            },
+            // The oldest video was dropped by TikTok and two new ones were watched
+            "Your Activity": { "Watch History": { "VideoList": [
+                { "Date": "2024-03-04 08:00:00", "Link": "https://www.tiktokv.com/share/video/4/" },
+                { "Date": "2024-03-03 08:00:00", "Link": "https://www.tiktokv.com/share/video/3/" },
+                { "Date": "2024-03-02 08:00:00", "Link": "https://www.tiktokv.com/share/video/1/" }
+            ]}},
+            "Post": { "Posts": { "VideoList": [
+                { "Date": "2024-02-05 08:00:00", "Link": "https://www.tiktokv.com/share/video/11/" }
+            ]}}
+        });
+
+        let diff = crate::diff_exports(&old, &new, &options(AnalysisWindow::default()));
+        let watched = diff.counts.iter().find(|count| count.name == "Videos watched").unwrap();
+        assert_eq!((watched.old, watched.new, watched.added), (2, 3, 2));
+        assert_eq!(diff.new_followers, vec!["carol"]);
+        assert_eq!(diff.unfollowers, vec!["alice"]);
+        assert_eq!(diff.deleted_posts.len(), 1);
+        assert_eq!(diff.deleted_posts[0].link, "https://www.tiktokv.com/share/video/10/");
+        assert_eq!(diff.profile_changes.len(), 1);
+        assert_eq!(diff.profile_changes[0].field, "bioDescription");
+        assert_eq!(diff.profile_changes[0].new.as_deref(), Some("new bio"));
+        let watch_rate = diff.rate_changes.iter().find(|rate| rate.name == "Videos watched per day").unwrap();
+        assert_eq!((watch_rate.old, watch_rate.new), (2.0, 1.5));
+        assert!(crate::render_diff(&diff, false).contains("Unfollowed you (1) : alice"));
+
+        // Both exports are cut to the window before they are compared
+        let march = AnalysisWindow::parse(Some("2024-03"), None, &new, &utc()).unwrap();
+        let diff = crate::diff_exports(&old, &new, &options(march));
+        let watched = diff.counts.iter().find(|count| count.name == "Videos watched").unwrap();
+        assert_eq!((watched.old, watched.new, watched.added), (2, 3, 2));
+        assert!(diff.deleted_posts.is_empty());
+        assert_eq!(diff.new_followers, vec!["carol"]);
+        assert!(diff.unfollowers.is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_every_event_once() {
+        let older = serde_json::json!({
+            "Profile": { "Profile Info": { "userName": "john.doe", "bioDescription": "old bio" } },
+            "Your Activity": { "Watch History": { "VideoList": [
+                { "Date": "2024-03-02 08:00:00", "Link": "https://www.tiktokv.com/share/video/2/" },
+                { "Date": "2024-03-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/1/" }
+            ]}},
+            "Direct Message": { "Direct Messages": { "ChatHistory": { "Chat History with bob:": [
+                { "Date": "2024-03-01 09:00:00", "From": "bob", "Content": "hi" },
+                { "Date": "2024-03-01 09:00:00", "From": "bob", "Content": "there" }
+            ]}}}
+        });
+        let newer = serde_json::json!({
+            "Profile": { "Profile Info": { "userName": "john.doe", "bioDescription": "new bio" } },
+            "Your Activity": { "Watch History": { "VideoList": [
+                { "Date": "2024-03-03 08:00:00", "Link": "https://www.tiktokv.com/share/video/3/" },
+                { "Date": "2024-03-02 08:00:00", "Link": "https://www.tiktokv.com/share/video/2/" }
+            ]}},
+            "Direct Message": { "Direct Messages": { "ChatHistory": { "Chat History with bob:": [
+                { "Date": "2024-03-01 09:00:00", "From": "bob", "Content": "there" }
+            ]}}}
+        });
+
+        // The order of the files doesn't matter
+        let merged = crate::merge_exports(vec![newer.clone(), older.clone()]).unwrap();
+        assert_eq!(merged, crate::merge_exports(vec![older, newer]).unwrap());
+
+        let videos = merged["Your Activity"]["Watch History"]["VideoList"].as_array().unwrap();
+        let dates: Vec<&str> = videos.iter().map(|video| video["Date"].as_str().unwrap()).collect();
+        assert_eq!(dates, vec!["2024-03-03 08:00:00", "2024-03-02 08:00:00", "2024-03-01 08:00:00"]);
+        // Messages sent in the same second are different events
+        let chat = &merged["Direct Message"]["Direct Messages"]["ChatHistory"]["Chat History with bob:"];
+        assert_eq!(chat.as_array().unwrap().len(), 2);
+        assert_eq!(merged["Profile"]["Profile Info"]["bioDescription"], "new bio");
+
+        let other_account = serde_json::json!({ "Profile": { "Profile Info": { "userName": "jane" } } });
+        assert!(crate::merge_exports(vec![merged, other_account]).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_oldest_first_lists_in_their_order() {
+        let comments = |comments: &[(&str, &str)]| {
+            let list: Vec<serde_json::Value> = comments
+                .iter()
+                .map(|(date, comment)| serde_json::json!({ "date": date, "comment": comment }))
+                .collect();
+            serde_json::json!({ "Comment": { "Comments": { "CommentsList": list } } })
+        };
+        // TikTok lists comments from the oldest to the most recent
+        let older = comments(&[("2024-03-01 08:00:00", "first"), ("2024-03-02 08:00:00", "second")]);
+        let newer = comments(&[("2024-03-02 08:00:00", "second"), ("2024-03-03 08:00:00", "third")]);
+
+        let before = crate::Statistics::build(newer.clone()).comment_info.unwrap();
+        let merged = crate::merge_exports(vec![newer, older]).unwrap();
+        let after = crate::Statistics::build(merged).comment_info.unwrap();
+        assert_eq!((before.first.content.as_str(), before.last.content.as_str()), ("second", "third"));
+        assert_eq!((after.first.content.as_str(), after.last.content.as_str()), ("first", "third"));
+    }
+
+    #[test]
+    fn archive_stores_each_record_once() {
+        let dir = std::env::temp_dir().join(format!("tiktok_archive_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let archive = crate::Archive::open(&dir).unwrap();
+        assert!(archive.lifetime().unwrap().is_none());
+
+        let export = |dates: &[&str]| {
+            let videos: Vec<serde_json::Value> = dates
+                .iter()
+                .map(|date| serde_json::json!({ "Date": date, "Link": format!("https://www.tiktokv.com/share/video/{}/", &date[8..10]) }))
+                .collect();
+            serde_json::json!({
+                "Profile": { "Profile Info": { "userName": "john.doe" } },
+                "Your Activity": { "Watch History": { "VideoList": videos } }
+            })
+        };
+        let older = export(&["2024-03-02 08:00:00", "2024-03-01 08:00:00"]);
+        let newer = export(&["2024-03-03 08:00:00", "2024-03-02 08:00:00"]);
+
+        assert!(matches!(archive.ingest(&older).unwrap(), crate::Ingested::Added(ref s) if s.records == 2));
+        assert!(matches!(archive.ingest(&newer).unwrap(), crate::Ingested::Added(ref s) if s.records == 1));
+        assert!(matches!(archive.ingest(&older).unwrap(), crate::Ingested::AlreadyArchived));
+        assert_eq!(archive.snapshots().unwrap().len(), 2);
+
+        let lifetime = archive.lifetime().unwrap().unwrap();
+        let statistics = crate::Statistics::build_with(lifetime, &options(AnalysisWindow::default()));
+        assert_eq!(statistics.watched.videos_watched, 3);
+
+        let other_account = serde_json::json!({ "Profile": { "Profile Info": { "userName": "jane" } } });
+        assert!(archive.ingest(&other_account).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn archive_keeps_profile_changes_without_new_records() {
+        let dir = std::env::temp_dir().join(format!("tiktok_archive_profile_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let archive = crate::Archive::open(&dir).unwrap();
+
+        let export = |bio: &str| {
+            serde_json::json!({
+                "Profile": { "Profile Info": { "userName": "john.doe", "bioDescription": bio } },
+                "Your Activity": { "Watch History": { "VideoList": [
+                    { "Date": "2024-03-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/1/" }
+                ]}}
+            })
+        };
+
+        assert!(matches!(archive.ingest(&export("old bio")).unwrap(), crate::Ingested::Added(ref s) if s.records == 1));
+        // Only the bio changed
+        assert!(matches!(archive.ingest(&export("new bio")).unwrap(), crate::Ingested::Added(ref s) if s.records == 0));
+        assert!(matches!(archive.ingest(&export("new bio")).unwrap(), crate::Ingested::AlreadyArchived));
+
+        let lifetime = archive.lifetime().unwrap().unwrap();
+        assert_eq!(lifetime["Profile"]["Profile Info"]["bioDescription"], "new bio");
+        let statistics = crate::Statistics::build_with(lifetime, &options(AnalysisWindow::default()));
+        assert_eq!(statistics.watched.videos_watched, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn archive_counts_the_entries_of_record_maps() {
+        let dir = std::env::temp_dir().join(format!("tiktok_archive_lives_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let archive = crate::Archive::open(&dir).unwrap();
+
+        let export = |lives: &[&str]| {
+            let lives: serde_json::Map<String, serde_json::Value> = lives
+                .iter()
+                .map(|id| {
+                    let live = serde_json::json!({ "WatchTime": "2024-03-01 20:00:00", "Link": "https://www.tiktok.com/@streamer/live" });
+                    (id.to_string(), live)
+                })
+                .collect();
+            serde_json::json!({
+                "Profile": { "Profile Info": { "userName": "john.doe" } },
+                "Your Activity": { "Watch History": { "VideoList": [
+                    { "Date": "2024-03-01 08:00:00", "Link": "https://www.tiktokv.com/share/video/1/" }
+                ]}},
+                "Tiktok Live": { "Watch Live History": { "WatchLiveMap": lives } }
+            })
+        };
+
+        assert!(matches!(archive.ingest(&export(&["1"])).unwrap(), crate::Ingested::Added(ref s) if s.records == 2));
+        // The only new record is a live
+        assert!(matches!(archive.ingest(&export(&["1", "2"])).unwrap(), crate::Ingested::Added(ref s) if s.records == 1));
+        assert!(matches!(archive.ingest(&export(&["2"])).unwrap(), crate::Ingested::AlreadyArchived));
+
+        let lifetime = archive.lifetime().unwrap().unwrap();
+        let statistics = crate::Statistics::build_with(lifetime, &options(AnalysisWindow::default()));
+        assert_eq!(statistics.live.lives_watched, 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn comparison_gives_percentiles_across_accounts() {
+        let account = |name: &str, comments: usize| {
+            let list: Vec<serde_json::Value> = (0..comments)
+                .map(|i| serde_json::json!({ "date": format!("2024-03-{:02} 08:00:00", i + 1), "comment": "hi" }))
+                .collect();
+            let data = serde_json::json!({
+                "Profile": { "Profile Info": { "userName": name } },
+                "Comment": { "Comments": { "CommentsList": list } }
+            });
+            (name.to_string(), crate::Statistics::build_with(data, &options(AnalysisWindow::default())))
+        };
+        let accounts = vec![account("a", 1), account("b", 4), account("c", 2), account("d", 3)];
+
+        let mut comparison = crate::compare_accounts(&accounts, 2);
+        assert_eq!(comparison.accounts.len(), 4);
+        assert_eq!(comparison.accounts[1].values["comments"], 4.0);
+        let comments = comparison.summary.iter().find(|summary| summary.metric == "comments").unwrap();
+        assert_eq!((comments.min, comments.max, comments.mean), (1.0, 4.0, 2.5));
+        assert_eq!(comments.percentiles["p50"], 2.5);
+        assert_eq!(comments.percentiles["p25"], 1.75);
+
+        let rows = comparison.csv_rows();
+        assert_eq!(rows.len(), 4 + 8);
+        assert_eq!(rows[4][0], "min");
+        assert_eq!(comparison.csv_header().len(), rows[0].len());
+
+        comparison.redact();
+        assert_eq!(comparison.accounts[1].account, "Account 2");
+        assert_eq!(comparison.accounts[1].username, "Account 2");
+    }
+
+    #[test]
+    fn anonymize_keeps_the_statistics_without_personal_data() {
+        let data = serde_json::json!({
+            "Profile": {
+                "Auto Fill": { "Email": "john@example.com", "PhoneNumber": "0601020304", "Country": "France" },
+                "Profile Info": { "userName": "john.doe", "emailAddress": "john@example.com" }
+            },
+            "Your Activity": {
+                "Login History": { "LoginHistoryList": [
+                    { "Date": "2024-03-01 10:00:00", "IP": "85.1.2.3", "GpsData": "48.85, 2.35" },
+                    { "Date": "2024-03-02 10:00:00", "IP": "2a01:cb00:1:2::5" }
+                ] },
+                "Purchases": { "SendGifts": { "SendGifts": [{ "Date": "2024-03-01 20:10:00", "GiftAmount": "5", "UserName": "alex_0" }] } },
+                "Most Recent Location Data": { "LocationData": { "Date": "2024-03-02 10:00:00", "LastRegion": "FR", "GpsData": "48.85, 2.35" } }
+            },
+            "Tiktok Live": { "Watch Live History": { "WatchLiveMap": { "7300000000000000001": {
+                "WatchTime": "2024-03-01 20:00:00",
+                "Link": "https://www.tiktok.com/@alex_0/live",
+                "Comments": [{ "CommentTime": "2024-03-01 20:05:00", "CommentContent": "see you at the station" }]
+            } } } },
+            "Comment": { "Comments": { "CommentsList": [{ "date": "2024-03-01 08:00:00", "comment": "call me at 0601020304" }] } },
+            "Direct Message": { "Direct Messages": { "ChatHistory": { "Chat History with bob:": [
+                { "Date": "2024-03-01 08:00:00", "From": "bob", "Content": "hi" },
+                { "Date": "2024-03-01 09:00:00", "From": "john.doe", "Content": "hello" }
+            ] } } }
+        });
+        let settings = crate::AnonymizeOptions { salt: String::from("salt"), remove_text: true };
+        let anonymized = crate::anonymize(&data, &settings);
+
+        let john = crate::pseudonym("john.doe", "salt");
+        let bob = crate::pseudonym("bob", "salt");
+        assert_ne!(john, crate::pseudonym("john.doe", "other salt"));
+        assert_eq!(anonymized["Profile"]["Profile Info"]["userName"], john.as_str());
+        assert!(anonymized["Profile"]["Profile Info"].get("emailAddress").is_none());
+        assert_eq!(anonymized["Profile"]["Auto Fill"], serde_json::json!({ "Country": "France" }));
+
+        let logins = &anonymized["Your Activity"]["Login History"]["LoginHistoryList"];
+        assert_eq!(logins[0]["IP"], "85.1.2.0");
+        assert_eq!(logins[1]["IP"], "2a01:cb00:1::");
+        assert!(logins[0].get("GpsData").is_none());
+        let location = &anonymized["Your Activity"]["Most Recent Location Data"]["LocationData"];
+        assert_eq!(location, &serde_json::json!({ "Date": "2024-03-02 10:00:00" }));
+
+        let alex = crate::pseudonym("alex_0", "salt");
+        let live = &anonymized["Tiktok Live"]["Watch Live History"]["WatchLiveMap"]["7300000000000000001"];
+        assert_eq!(live["Link"], format!("https://www.tiktok.com/@{}/live", alex));
+        assert_eq!(live["Comments"][0]["CommentContent"], "[removed]");
+        assert_eq!(anonymized["Your Activity"]["Purchases"]["SendGifts"]["SendGifts"][0]["UserName"], alex.as_str());
+
+        let chat = &anonymized["Direct Message"]["Direct Messages"]["ChatHistory"][format!("Chat History with {}:", bob)];
+        assert_eq!(chat[0]["From"], bob.as_str());
+        assert_eq!(chat[1]["From"], john.as_str());
+        assert_eq!(chat[1]["Content"], "[removed]");
+
+        let before = crate::Statistics::build_with(data, &options(AnalysisWindow::default()));
+        let after = crate::Statistics::build_with(anonymized, &options(AnalysisWindow::default()));
+        assert_eq!(after.username, john);
+        assert_eq!(after.logins.openings, before.logins.openings);
+        assert_eq!(after.comments, before.comments);
+        assert_eq!(after.dms[&format!("Chat with {}:", bob)], 2);
+        assert_eq!(after.comment_info.unwrap().first.content, "[removed]");
+        assert_eq!(after.live.hosts.get(&alex), Some(&1));
+        assert_eq!(after.purchases.gift_recipients.get(&alex), Some(&5));
+    }
+
+    #[test]
+    fn redact_hides_messages_names_and_links() {
+        let data = serde_json::json!({
+            "Profile": {
+                "Profile Info": { "userName": "john.doe" },
+                "Follower List": { "FansList": [{ "Date": "2024-03-01 08:00:00", "UserName": "alice" }] },
+                "Following List": { "Following": [{ "Date": "2024-03-02 08:00:00", "UserName": "alice" }] }
+            },
+            "Your Activity": {
+                "Like List": { "ItemFavoriteList": [
+                    { "date": "2024-03-01 08:00:00", "link": "https://www.tiktokv.com/share/video/7200000000000000001/" }
+                ] },
+                "Login History": { "LoginHistoryList": [
+                    { "Date": "2024-03-01 08:00:00", "IP": "85.12.1.30" },
+                    { "Date": "2024-03-02 08:00:00", "IP": "85.12.2.30" }
+                ] }
+            },
+            "Comment": { "Comments": { "CommentsList": [{ "date": "2024-03-01 08:00:00", "comment": "secret" }] } },
+            "Direct Message": { "Direct Messages": { "ChatHistory": {
+                "Chat History with bob:": [{ "Date": "2024-03-01 08:00:00", "From": "bob", "Content": "hi" }],
+                "Chat History with alice:": [
+                    { "Date": "2024-03-02 08:00:00", "From": "alice", "Content": "hey" },
+                    { "Date": "2024-03-03 08:00:00", "From": "john.doe", "Content": "hello" },
+                    { "Date": "yesterday", "From": "alice", "Content": "bye" }
+                ]
+            } } }
+        });
+        let mut statistics = crate::Statistics::build_with(data.clone(), &options(AnalysisWindow::default()));
+        statistics.redact();
+
+        // Contacts are numbered from the biggest chat down, and keep their label in every section
+        assert_eq!(statistics.dms["Chat with Contact 1:"], 3);
+        assert_eq!(statistics.dms["Chat with Contact 2:"], 1);
+        let dm_info = statistics.dm_info.as_ref().unwrap();
+        assert_eq!(dm_info.first.content, "(in Chat History with Contact 2:) [message hidden]");
+        assert_eq!(dm_info.last.content, "(in Chat History with Contact 1:) [message hidden]");
+        assert_eq!(statistics.follows.mutuals, vec!["Contact 1"]);
+        assert_eq!(statistics.follows.follower_info.as_ref().unwrap().first.content, "Contact 1");
+        assert_eq!(statistics.comment_info.as_ref().unwrap().first.content, "[message hidden]");
+        assert_eq!(statistics.like_info.as_ref().unwrap().last.content, "[link hidden]");
+        assert_eq!(statistics.comments, 1);
+        let ips = statistics.location.fields.iter().find(|field| field.flagged).unwrap();
+        assert_eq!(ips.values.len(), 2);
+        assert_eq!(ips.values[0].value, "Location 1");
+        let unparsed = &statistics.unparsed_dates[0].path;
+        assert_eq!(unparsed, "Direct Message > Direct Messages > ChatHistory > Chat History with Contact 1:");
+
+        let printed = serde_json::to_string(&statistics).unwrap();
+        for hidden in ["alice", "bob", "secret", "hello", "tiktokv.com", "85.12"] {
+            assert!(!printed.contains(hidden), "{} is still in the results", hidden);
+        }
+
+        let mut changed = data.clone();
+        changed["Profile"]["Profile Info"]["emailAddress"] = serde_json::json!("john@example.com");
+        changed["Profile"]["Profile Info"]["bioDescription"] = serde_json::json!("my secret bio");
+        let mut diff = crate::diff_exports(&data, &changed, &options(AnalysisWindow::default()));
+        diff.redact();
+        assert_eq!(diff.profile_changes.len(), 2);
+        assert_eq!(diff.profile_changes[0].old, None);
+        let printed = serde_json::to_string(&diff).unwrap();
+        for hidden in ["john@example.com", "my secret bio", "alice"] {
+            assert!(!printed.contains(hidden), "{} is still in the diff", hidden);
+        }
+    }
+
+    #[test]
+    fn generated_exports_are_reproducible_and_complete() {
+        let generator = crate::GeneratorOptions { seed: 42, days: 30, users: 12, ..Default::default() };
+        let export = crate::generate_export(&generator);
+        assert_eq!(export, crate::generate_export(&generator));
+        assert_ne!(export, crate::generate_export(&crate::GeneratorOptions { seed: 43, ..generator }));
+
+        assert!(crate::missing_sections(&export).is_empty());
+        assert!(crate::find_unparsed_dates(&export).is_empty());
+
+        let light = crate::generate_export(&crate::GeneratorOptions {
+            usage: crate::UsagePattern::Light,
+            days: 30,
+            ..Default::default()
+        });
+        let statistics = crate::Statistics::build_with(export, &options(AnalysisWindow::default()));
+        let light = crate::Statistics::build_with(light, &options(AnalysisWindow::default()));
+        assert_eq!(statistics.username, "demo.user");
+        assert!(statistics.watched.videos_watched > light.watched.videos_watched);
+        assert!(statistics.rewatches.rewatched_videos > 0);
+        assert!(statistics.follows.followers > 0 && !statistics.dms.is_empty());
+        assert!(statistics.watched.rate.from.as_deref().is_some_and(|from| from.starts_with("2024-01")));
+    }
+
+    #[test]
+    fn prices_use_their_last_separator_as_decimal_point() {
+        let data = serde_json::json!({
+            "Profile": { "Profile Info": { "userName": "john.doe" } },
+            "Your Activity": { "Purchases": { "BuyGifts": { "BuyGifts": [
+                { "Date": "2024-03-01 08:00:00", "Price": "1.234,56 €" },
+                { "Date": "2024-03-02 08:00:00", "Price": "1,234.56" },
+                { "Date": "2024-03-03 08:00:00", "Price": "1,99 €" }
+            ] } } }
+        });
+        let statistics = crate::Statistics::build_with(data, &options(AnalysisWindow::default()));
+        let spend = &statistics.purchases.spend_by_currency;
+
+        assert!((spend["EUR"] - (1234.56 + 1.99)).abs() < 1e-9);
+        assert!((spend["Unknown"] - 1234.56).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prices_without_decimals_keep_their_thousands() {
+        let data = serde_json::json!({
+            "Your Activity": { "Purchases": { "BuyGifts": { "BuyGifts": [
+                { "Date": "2024-03-01 08:00:00", "Price": "$1,234" },
+                { "Date": "2024-03-02 08:00:00", "Price": "1.000 €" },
+                { "Date": "2024-03-03 08:00:00", "Price": "1.234,56 €" }
+            ] } } },
+            // Some exports store the order date as epoch seconds
+            "Tiktok Shopping": { "Order History": { "OrderHistories": {
+                "1": { "order_date": 1709280000, "total_price": "£2,500" }
+            }}}
+        });
+        let purchases = super::purchases::purchase_stats(&data, &utc());
+
+        assert_eq!(purchases.spend_by_currency.get("USD"), Some(&1234.0));
+        assert!((purchases.spend_by_currency["EUR"] - (1000.0 + 1234.56)).abs() < 1e-9);
+        assert_eq!(purchases.spend_per_month["2024-03"].get("GBP"), Some(&2500.0));
+    }
 }
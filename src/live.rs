@@ -0,0 +1,141 @@
+// live.rs
+// live.rs is in charge of :
+// - reading the "Tiktok Live" section (lives watched, comments posted during lives, lives hosted)
+// - finding the hosts watched the most and how long the user's own lives lasted
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{count_per_month, date_field, date_info_by_timestamp, date_utils, str_field, ActivityItem, DateInfo, UserTimeZone};
+
+#[derive(Serialize)]
+pub struct HostedLive {
+    pub date: String,
+    pub duration_minutes: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LiveStats {
+    pub lives_watched: usize,
+    pub watched_per_month: BTreeMap<String, usize>,
+    pub hosts: HashMap<String, usize>,
+    pub watch_info: Option<DateInfo>,
+    pub comments: usize,
+    pub comment_info: Option<DateInfo>,
+    pub gifts_sent: usize,
+    pub hosted: Vec<HostedLive>,
+    pub hosted_minutes: u64,
+}
+
+pub fn live_stats(data: &Value, tz: &UserTimeZone) -> LiveStats {
+    let live = &data["Tiktok Live"];
+
+    // Watched lives are stored in a map keyed by room ID, older exports use a list
+    let watched: Vec<&Value> = match &live["Watch Live History"]["WatchLiveMap"] {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(list) => list.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let mut hosts = HashMap::new();
+    let mut watched_items = Vec::new();
+    let mut comment_items = Vec::new();
+    let mut gifts_sent = 0;
+
+    for entry in watched.iter() {
+        let link = str_field(entry, &["Link", "link"]).unwrap_or("");
+        let host = str_field(entry, &["HostName", "Host", "AnchorName"])
+            .map(|host| host.to_string())
+            .or_else(|| host_from_link(link))
+            .unwrap_or_else(|| String::from("Unknown host"));
+        *hosts.entry(host.clone()).or_insert(0) += 1;
+
+        watched_items.push(ActivityItem::new(
+            date_field(entry, &["WatchTime", "Date"]).unwrap_or_default(),
+            host.clone(),
+        ));
+
+        for comment in entry["Comments"].as_array().into_iter().flatten() {
+            comment_items.push(ActivityItem::new(
+                date_field(comment, &["CommentTime", "Date"]).unwrap_or_default(),
+                format!(
+                    "(in {}'s live) {}",
+                    host,
+                    str_field(comment, &["CommentContent", "Comment"]).unwrap_or("")
+                ),
+            ));
+        }
+        gifts_sent += entry["Gifts"].as_array().map(|gifts| gifts.len()).unwrap_or(0);
+    }
+
+    let hosted: Vec<HostedLive> = live["Go Live History"]["GoLiveList"]
+        .as_array()
+        .map(|list| list.iter().map(hosted_live).collect())
+        .unwrap_or_default();
+    let hosted_minutes = hosted.iter().filter_map(|live| live.duration_minutes).sum();
+
+    LiveStats {
+        lives_watched: watched.len(),
+        watched_per_month: count_per_month(&watched_items, tz),
+        hosts,
+        watch_info: date_info_by_timestamp(&watched_items),
+        comments: comment_items.len(),
+        comment_info: date_info_by_timestamp(&comment_items),
+        gifts_sent,
+        hosted,
+        hosted_minutes,
+    }
+}
+
+// Live links look like "https://www.tiktok.com/@host/live"
+fn host_from_link(link: &str) -> Option<String> {
+    let start = link.find('@')? + 1;
+    let host: String = link[start..]
+        .chars()
+        .take_while(|c| *c != '/' && *c != '?')
+        .collect();
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+fn hosted_live(entry: &Value) -> HostedLive {
+    let start = date_field(entry, &["StartTime", "Start Time", "Date"]).unwrap_or_default();
+    let end = date_field(entry, &["EndTime", "End Time"]);
+
+    // Either an explicit duration, or the difference between the end and the start of the live
+    let duration_minutes = match entry.get("Duration") {
+        Some(Value::Number(seconds)) => seconds.as_u64().map(|s| s / 60),
+        Some(Value::String(duration)) => parse_duration_minutes(duration),
+        _ => None,
+    }
+    .or_else(|| {
+        let start_ts = date_utils::date_to_unix_timestamp(&start)?;
+        let end_ts = date_utils::date_to_unix_timestamp(&end?)?;
+        (end_ts >= start_ts).then(|| ((end_ts - start_ts) / 60) as u64)
+    });
+
+    HostedLive {
+        date: start.to_string(),
+        duration_minutes,
+    }
+}
+
+// Durations are exported either as "HH:MM:SS" or as a number of seconds
+fn parse_duration_minutes(duration: &str) -> Option<u64> {
+    let parts: Vec<u64> = duration
+        .trim()
+        .split(':')
+        .map(|part| part.trim().parse::<u64>())
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match parts.as_slice() {
+        [seconds] => Some(seconds / 60),
+        [minutes, _] => Some(*minutes),
+        [hours, minutes, _] => Some(hours * 60 + minutes),
+        _ => None,
+    }
+}
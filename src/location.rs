@@ -0,0 +1,150 @@
+// location.rs
+// location.rs is in charge of :
+// - walking the whole export to collect every location-related field (IP addresses, regions, GPS data, addresses...)
+// - listing the distinct values of each field with the first and last time they were seen
+// - flagging the precise ones, that the user may want to delete from their account
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::{date_utils, record_date};
+
+#[derive(Serialize)]
+pub struct LocationValue {
+    pub value: String,
+    pub occurrences: usize,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LocationField {
+    pub path: String,
+    pub values: Vec<LocationValue>,
+    pub flagged: bool,
+}
+
+#[derive(Serialize)]
+pub struct LocationStats {
+    pub fields: Vec<LocationField>,
+}
+
+// Field names are compared lowercased and without spaces or underscores
+const PRECISE_FIELDS: [&str; 9] = [
+    "ip", "ipaddress", "gpsdata", "gps", "latitude", "longitude", "address", "zipcode", "city",
+];
+const COARSE_FIELDS: [&str; 7] = [
+    "country", "countrycode", "state", "region", "lastregion", "appregion", "location",
+];
+const PLACEHOLDERS: [&str; 5] = ["", "n/a", "none", "null", "unknown"];
+
+#[derive(Default)]
+struct Sighting {
+    occurrences: usize,
+    first: Option<(i64, String)>,
+    last: Option<(i64, String)>,
+}
+
+pub fn location_stats(data: &Value) -> LocationStats {
+    let mut found: BTreeMap<(String, bool), BTreeMap<String, Sighting>> = BTreeMap::new();
+    collect(data, &mut Vec::new(), &mut found);
+
+    let fields = found
+        .into_iter()
+        .map(|((path, flagged), values)| {
+            let mut values: Vec<LocationValue> = values
+                .into_iter()
+                .map(|(value, sighting)| LocationValue {
+                    value,
+                    occurrences: sighting.occurrences,
+                    first_seen: sighting.first.map(|(_, date)| date),
+                    last_seen: sighting.last.map(|(_, date)| date),
+                })
+                .collect();
+            values.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then(a.value.cmp(&b.value)));
+            LocationField {
+                path,
+                values,
+                flagged,
+            }
+        })
+        .collect();
+
+    LocationStats { fields }
+}
+
+fn collect<'a>(
+    value: &'a Value,
+    path: &mut Vec<&'a str>,
+    found: &mut BTreeMap<(String, bool), BTreeMap<String, Sighting>>,
+) {
+    match value {
+        Value::Object(map) => {
+            let date = record_date(value);
+            for (key, child) in map {
+                // Maps keyed by IDs (lives, orders...) would otherwise give one path per entry
+                let segment = if key.chars().all(|c| c.is_ascii_digit()) { "*" } else { key.as_str() };
+                path.push(segment);
+                match (location_kind(key), child.as_str()) {
+                    (Some(flagged), Some(text)) if !is_placeholder(text) => {
+                        record(found, path.join(" > "), flagged, text.trim(), date.as_deref())
+                    }
+                    _ => collect(child, path, found),
+                }
+                path.pop();
+            }
+        }
+        Value::Array(list) => {
+            for child in list {
+                collect(child, path, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Some(true) for precise location fields, Some(false) for coarse ones, None for anything else
+fn location_kind(key: &str) -> Option<bool> {
+    let normalized: String = key
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .collect::<String>()
+        .to_lowercase();
+    if PRECISE_FIELDS.contains(&normalized.as_str()) {
+        Some(true)
+    } else if COARSE_FIELDS.contains(&normalized.as_str()) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn is_placeholder(text: &str) -> bool {
+    PLACEHOLDERS.contains(&text.trim().to_lowercase().as_str())
+}
+
+fn record(
+    found: &mut BTreeMap<(String, bool), BTreeMap<String, Sighting>>,
+    path: String,
+    flagged: bool,
+    value: &str,
+    date: Option<&str>,
+) {
+    let sighting = found
+        .entry((path, flagged))
+        .or_default()
+        .entry(value.to_string())
+        .or_default();
+    sighting.occurrences += 1;
+
+    let Some((date, ts)) = date.and_then(|d| date_utils::date_to_unix_timestamp(d).map(|ts| (d, ts))) else {
+        return;
+    };
+    if sighting.first.as_ref().is_none_or(|(first_ts, _)| ts < *first_ts) {
+        sighting.first = Some((ts, date.to_string()));
+    }
+    if sighting.last.as_ref().is_none_or(|(last_ts, _)| ts > *last_ts) {
+        sighting.last = Some((ts, date.to_string()));
+    }
+}
@@ -1,189 +1,639 @@
 // main.rs
 // main.rs is in charge of :
+// - reading the command line (described in cli.rs) and running the chosen command
 // - calling the functions in read_file.rs to get the data from the JSON file
 // - calling the lib.rs "build" function with the data as an argument -
 // that will build an instance of the "Statistics" struct which contains the processed data
-// - print the data to the console in a user-friendly way
+// - print the data to the console in a user-friendly way, or in a format other tools can read
 
+mod cli;
 mod read_file;
-use std::process;
 
+use clap::Parser;
+use serde::Serialize;
 use serde_json::Value;
-use tiktok_json_analyzer::Statistics;
-mod chart_utils;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process;
+use tiktok_json_analyzer::{
+    anonymize, build_inventory, build_timeline, compare_accounts, diff_exports,
+    find_unparsed_dates, flatten_json, generate_export, merge_exports, missing_sections, paint,
+    profile_info, render_diff, render_report, report_json, select_sections, to_csv,
+    AnalysisOptions, AnalysisWindow, AnonymizeOptions, Archive, GeneratorOptions, Ingested,
+    ReferenceDate, ReportSection, Statistics, TimelineStep, UnparsedDates, UsagePattern,
+    UserTimeZone,
+};
+
+use cli::{AnalysisArgs, ArchiveAction, Cli, Command, Format, Input, Step, Usage};
+
+// Settings shared by every command
+struct Output {
+    requested: Option<Format>,
+    // Set once the command has picked its format
+    format: Cell<Format>,
+    banner_printed: Cell<bool>,
+    quiet: bool,
+    color: bool,
+    redact: bool,
+}
+
+impl Output {
+    // The format asked with --format, or the first one the command supports
+    fn format(&self, command: &str, supported: &[Format]) -> Format {
+        let format = match self.requested {
+            None => supported[0],
+            Some(format) if supported.contains(&format) => format,
+            Some(_) => {
+                let names: Vec<&str> = supported.iter().map(|format| format_name(*format)).collect();
+                eprintln!("\u{274C} ERROR: The {command} command can only write {}.", names.join(", "));
+                process::exit(1);
+            }
+        };
+        self.format.set(format);
+        format
+    }
+
+    fn heading(&self, title: &str) {
+        let heading = format!("---------- {} ----------", title);
+        println!("\n{}", paint(&heading, "1;36", self.color));
+    }
+
+    // Warnings go to the error output when the results are meant for other tools
+    fn warning(&self, message: &str) {
+        let warning = paint(&format!("\u{26A0} {}", message), "33", self.color);
+        if self.format.get() == Format::Text {
+            println!("{}", warning);
+        } else {
+            eprintln!("{}", warning);
+        }
+    }
+
+    // Progress messages are left out with --quiet and when the output is meant for other tools
+    fn progress(&self, message: &str) {
+        if self.quiet || self.format.get() != Format::Text {
+            return;
+        }
+        if !self.banner_printed.replace(true) {
+            println!("---------- The TikTok JSON Analyzer ----------");
+            println!("This program reads the TikTok JSON data export and calculates statistics");
+            println!(
+                "Read the documentation at https://github.com/Elazrod56/tiktok_json_analyzer#readme\n"
+            );
+        }
+        println!("{}", message);
+    }
+}
+
+fn format_name(format: Format) -> &'static str {
+    match format {
+        Format::Text => "text",
+        Format::Json => "json",
+        Format::Csv => "csv",
+    }
+}
 
 fn main() {
-    println!("---------- The TikTok JSON Analyzer ----------");
-    println!("This program reads the TikTok JSON data export and calculates statistics");
-    println!(
-        "Read the documentation at https://github.com/Elazrod56/tiktok_json_analyzer#readme\n"
-    );
+    let cli = Cli::parse();
+    let color = !cli.no_color && std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal();
+    let output = Output {
+        requested: cli.format,
+        format: Cell::new(Format::Text),
+        banner_printed: Cell::new(false),
+        quiet: cli.quiet,
+        color,
+        redact: cli.redact,
+    };
+
+    match cli.command {
+        Command::Report { input, section } => report(&input, &section, &output),
+        Command::Inventory { input } => inventory(&input, &output),
+        Command::Export { input, output: path } => export(&input, path, &output),
+        Command::Diff { old, new, options } => diff(&old, &new, &options, &output),
+        Command::Merge { files, output: path } => merge(&files, path, &output),
+        Command::Anonymize { file, salt, remove_text, output: path } => {
+            anonymize_export(&file, AnonymizeOptions { salt, remove_text }, path, &output)
+        }
+        Command::Compare { dir, options, output: path } => compare(&dir, &options, path, &output),
+        Command::Archive { action } => archive(action, &output),
+        Command::Timeline { input, by } => timeline(&input, by, &output),
+        Command::Generate { seed, username, users, start, days, usage, output: path } => {
+            let start = chrono::NaiveDate::parse_from_str(&start, "%Y-%m-%d").unwrap_or_else(|err| {
+                eprintln!("\u{274C} ERROR: Invalid start date \"{start}\" - {err}");
+                eprintln!("Use a day like 2024-01-01.");
+                process::exit(1);
+            });
+            let usage = match usage {
+                Usage::Light => UsagePattern::Light,
+                Usage::Regular => UsagePattern::Regular,
+                Usage::Heavy => UsagePattern::Heavy,
+            };
+            generate(GeneratorOptions { seed, username, users, start, days, usage }, path, &output)
+        }
+        Command::Validate { file } => validate(&file, &output),
+    }
+}
+
+fn report(input: &Input, section_names: &[String], output: &Output) {
+    let format = output.format("report", &[Format::Text, Format::Json]);
+    let sections = sections_or_exit(section_names);
+    let data = load(&input.file, output);
+    let options = analysis_options(&input.options, &data, &input.file);
+    let statistics = analyze(data, &options, output);
+    print_report(&statistics, &sections, format, output);
+}
 
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("\u{274C} ERROR: No JSON file path provided.");
-        eprintln!("Usage: cargo run -- <path_to_your_json_file>");
+// Builds the statistics, without content that would expose other people when --redact is set
+fn analyze(data: Value, options: &AnalysisOptions, output: &Output) -> Statistics {
+    let mut statistics = Statistics::build_with(data, options);
+    if output.redact {
+        statistics.redact();
+    }
+    statistics
+}
+
+fn sections_or_exit(section_names: &[String]) -> Vec<Box<dyn ReportSection>> {
+    select_sections(section_names).unwrap_or_else(|err| {
+        eprintln!("\u{274C} ERROR: {err}");
         process::exit(1);
+    })
+}
+
+fn print_report(statistics: &Statistics, sections: &[Box<dyn ReportSection>], format: Format, output: &Output) {
+    if format == Format::Json {
+        print_json(&report_json(statistics, sections));
+        return;
     }
-    let file_path = &args[1];
 
-    let file = read_file::file_into_str(file_path);
-    println!("\u{2705} File read successfully!");
+    output.progress("\u{2705} Data seems valid!\n");
+    print!("{}", render_report(statistics, sections, output.color));
+}
 
-    let data = read_file::str_into_object(file).unwrap_or_else(|err| {
-        eprintln!(
-            "\u{274C} ERROR when converting file into object : {err}\nFile is most likely invalid."
-        );
-        process::exit(2);
+fn inventory(input: &Input, output: &Output) {
+    let format = output.format("inventory", &[Format::Text, Format::Json, Format::Csv]);
+    let mut data = load(&input.file, output);
+    let options = analysis_options(&input.options, &data, &input.file);
+    options.window.apply(&mut data);
+    print_inventory(&data, format, output);
+}
+
+fn export(input: &Input, path: Option<PathBuf>, output: &Output) {
+    let format = output.format("export", &[Format::Json, Format::Csv]);
+    let data = load(&input.file, output);
+    let options = analysis_options(&input.options, &data, &input.file);
+    let statistics = analyze(data, &options, output);
+
+    let content = match format {
+        Format::Csv => {
+            let value = serde_json::to_value(&statistics).expect("statistics are always serializable");
+            let rows: Vec<Vec<String>> = flatten_json(&value)
+                .into_iter()
+                .map(|(field, value)| vec![field, value])
+                .collect();
+            to_csv(&["field", "value"], &rows)
+        }
+        _ => serde_json::to_string_pretty(&statistics).expect("statistics are always serializable") + "\n",
+    };
+
+    write_output(path, content, output, "Statistics");
+}
+
+fn diff(old_file: &str, new_file: &str, args: &AnalysisArgs, output: &Output) {
+    let format = output.format("diff", &[Format::Text, Format::Json]);
+    let old_data = load(old_file, output);
+    let new_data = load(new_file, output);
+    // The period, time zone and reference date are read from the newer export
+    let options = analysis_options(args, &new_data, new_file);
+    let mut diff = diff_exports(&old_data, &new_data, &options);
+    if output.redact {
+        diff.redact();
+    }
+
+    if format == Format::Json {
+        print_json(&diff);
+        return;
+    }
+    print!("{}", render_diff(&diff, output.color));
+}
+
+fn merge(files: &[String], path: Option<PathBuf>, output: &Output) {
+    output.format("merge", &[Format::Json]);
+    let exports: Vec<Value> = files.iter().map(|file| load(file, output)).collect();
+    let merged = merge_exports(exports).unwrap_or_else(|err| {
+        eprintln!("\u{274C} ERROR: The exports can't be merged - {err}");
+        process::exit(1);
     });
-    check_data_validity(&data);
-    println!("\u{2705} Data seems valid!\n");
+    let content = serde_json::to_string_pretty(&merged).expect("JSON values are always serializable") + "\n";
+    write_output(path, content, output, "Merged export");
+}
 
-    let statistics = Statistics::build(data);
-    println!(
-        "The data of {} has been analyzed. Results :",
-        statistics.username
-    );
+fn anonymize_export(file: &str, options: AnonymizeOptions, path: Option<PathBuf>, output: &Output) {
+    output.format("anonymize", &[Format::Json]);
+    let data = load(file, output);
+    let anonymized = anonymize(&data, &options);
+    let content = serde_json::to_string_pretty(&anonymized).expect("JSON values are always serializable") + "\n";
+    write_output(path, content, output, "Anonymized export");
+}
 
-    println!("\n---------- LOGINS \u{1F511} ----------");
-    println!(
-        "In the last {} days, you've launched TikTok {} times",
-        statistics.logins.get("Days since 1st login").unwrap(),
-        statistics.logins.get("Openings").unwrap()
-    ); // it's safe to unwrap because there would have been an error sooner if something was wrong
-       // it's also much more concise in terms of code length
-    println!(
-        "- {} launches per day on average",
-        statistics.logins.get("Launches per day").unwrap()
-    );
+// Every account is analyzed with the same options. Files that can't be analyzed are skipped with a warning
+fn compare(dir: &PathBuf, args: &AnalysisArgs, path: Option<PathBuf>, output: &Output) {
+    let format = output.format("compare", &[Format::Text, Format::Json, Format::Csv]);
+    let entries = std::fs::read_dir(dir).unwrap_or_else(|err| {
+        eprintln!("\u{274C} ERROR: Could not read {} - {err}", dir.display());
+        process::exit(1);
+    });
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|file| file.extension().is_some_and(|extension| extension == "json"))
+        .collect();
+    files.sort();
 
-    println!("\n---------- VIDEO CONSUMPTION \u{1F4FA} ----------");
-    println!(
-        "In the last {} days, you've watched {} videos",
-        statistics.watched.get("Days since 1st video").unwrap(),
-        statistics.watched.get("Videos watched").unwrap()
-    );
-    println!(
-        "- {} watched videos per day on average",
-        statistics.watched.get("Watched per day").unwrap()
-    );
-    if let Some(info) = statistics.watch_info {
-        println!("  - First seen: {} ({})", info.first.content, info.first.date);
-        println!("  - Last seen: {} ({})", info.last.content, info.last.date);
+    let mut accounts = Vec::new();
+    for file in files.iter() {
+        let file_path = file.to_string_lossy();
+        let data = match std::fs::read_to_string(file).map_err(|err| err.to_string()).and_then(|content| {
+            read_file::str_into_object(content).map_err(|err| err.to_string())
+        }) {
+            Ok(data) if !profile_info(&data).is_null() => data,
+            Ok(_) => {
+                output.warning(&format!("{} is not a TikTok export, skipped", file_path));
+                continue;
+            }
+            Err(err) => {
+                output.warning(&format!("{} can't be read ({}), skipped", file_path, err));
+                continue;
+            }
+        };
+        let options = analysis_options(args, &data, &file_path);
+        let account = file.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let mut statistics = Statistics::build_with(data, &options);
+        if output.redact {
+            statistics.redact();
+        }
+        accounts.push((account, statistics));
+    }
+    if accounts.is_empty() {
+        eprintln!("\u{274C} ERROR: No export was found in {}", dir.display());
+        process::exit(1);
     }
 
-    println!("\n---------- TIME SPENT DAILY \u{1F570}----------");
-    chart_utils::print_time_spent_chart(
-        (*statistics.watched.get("Watched per day").unwrap_or(&0) as f32 * 27.5 / 60.0) as usize,
-    );
-    println!("TikTok videos are on average 27.5 seconds long, so we can estimate how much time you spend on TikTok every day.");
-    println!(
-        "Time wasted on TikTok every day : {} on average",
-        statistics.time
-    );
-    println!("This stat is not 100% precise! The more you tend to not watch whole videos, the more time you can remove.");
+    let mut comparison = compare_accounts(&accounts, args.decimals);
+    if output.redact {
+        comparison.redact();
+    }
+    let content = match format {
+        Format::Json => serde_json::to_string_pretty(&comparison).expect("results are always serializable") + "\n",
+        Format::Csv => to_csv(&comparison.csv_header(), &comparison.csv_rows()),
+        Format::Text => {
+            // Columns are as wide as their longest value
+            let header = comparison.csv_header();
+            let rows = comparison.csv_rows();
+            let widths: Vec<usize> = (0..header.len())
+                .map(|column| {
+                    rows.iter()
+                        .map(|row| row[column].len())
+                        .chain([header[column].len()])
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            let line = |cells: Vec<&str>| -> String {
+                let cells: Vec<String> = cells
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect();
+                cells.join("  ").trim_end().to_string() + "\n"
+            };
+            let mut table = line(header.clone());
+            for (index, row) in rows.iter().enumerate() {
+                // The summary rows come after the accounts
+                if index == accounts.len() {
+                    table.push('\n');
+                }
+                table.push_str(&line(row.iter().map(String::as_str).collect()));
+            }
+            table
+        }
+    };
+    write_output(path, content, output, "Comparison");
+}
 
-    println!("\n---------- FAVORITES \u{1F60D} ----------");
-    println!(
-        "{} favorite effects",
-        statistics.favorites.get("Effects").unwrap()
-    );
-    println!(
-        "{} favorite hashtags",
-        statistics.favorites.get("Hashtags").unwrap()
-    );
-    println!(
-        "{} favorite sounds",
-        statistics.favorites.get("Sounds").unwrap()
-    );
-    println!(
-        "{} favorite videos",
-        statistics.favorites.get("Videos").unwrap()
-    );
+fn archive(action: ArchiveAction, output: &Output) {
+    let or_exit = |err: String| -> ! {
+        eprintln!("\u{274C} ERROR: {err}");
+        process::exit(1);
+    };
+    let open = |dir: &PathBuf| Archive::open(dir).unwrap_or_else(|err| or_exit(err));
 
-    println!("\n---------- LIKES \u{2764} ----------");
-    println!(
-        "You've liked {} videos in the last {} days",
-        statistics.likes_left.get("Videos liked").unwrap(),
-        statistics.likes_left.get("Days since oldest like").unwrap()
-    );
-    println!(
-        "You've liked {} videos per day on average\n",
-        statistics.likes_left.get("Likes per day").unwrap()
-    );
-    println!(
-        "You've liked {}% of the videos you've watched\n",
-        statistics
-            .likes_left
-            .get("Liked videos percentage")
-            .unwrap()
-    );
-    if let Some(info) = statistics.like_info {
-        println!("  - First liked video: {} ({})", info.first.content, info.first.date);
-        println!("  - Last liked video: {} ({})", info.last.content, info.last.date);
+    match action {
+        ArchiveAction::Add { dir, files } => {
+            let format = output.format("archive add", &[Format::Text, Format::Json]);
+            let archive = open(&dir);
+            let mut added = Vec::new();
+            for file in files.iter() {
+                let data = load(file, output);
+                match archive.ingest(&data).unwrap_or_else(|err| or_exit(err)) {
+                    Ingested::Added(snapshot) => {
+                        output.progress(&format!(
+                            "\u{2705} {} : {} new records saved in {}",
+                            file, snapshot.records, snapshot.file
+                        ));
+                        added.push(snapshot);
+                    }
+                    Ingested::AlreadyArchived => {
+                        output.progress(&format!("\u{2796} {} : every record was already archived", file))
+                    }
+                }
+            }
+            if format == Format::Json {
+                print_json(&added);
+            }
+        }
+        ArchiveAction::List { dir } => {
+            let format = output.format("archive list", &[Format::Text, Format::Json]);
+            let snapshots = open(&dir).snapshots().unwrap_or_else(|err| or_exit(err));
+            if format == Format::Json {
+                print_json(&snapshots);
+                return;
+            }
+            output.heading("ARCHIVED SNAPSHOTS \u{1F5C4}");
+            if snapshots.is_empty() {
+                println!("The archive is empty, add exports with \"archive add\"");
+            }
+            for snapshot in snapshots.iter() {
+                let dates = match (&snapshot.first_date, &snapshot.last_date) {
+                    (Some(first), Some(last)) => format!(" ({} -> {})", first, last),
+                    _ => String::new(),
+                };
+                println!("{} : {} records{}", snapshot.file, snapshot.records, dates);
+            }
+        }
+        ArchiveAction::Report { dir, options, section } => {
+            let format = output.format("archive report", &[Format::Text, Format::Json]);
+            let sections = sections_or_exit(&section);
+            let data = lifetime_or_exit(&open(&dir));
+            let options = analysis_options(&options, &data, &dir.to_string_lossy());
+            let statistics = analyze(data, &options, output);
+            print_report(&statistics, &sections, format, output);
+        }
+        ArchiveAction::Export { dir, output: path } => {
+            output.format("archive export", &[Format::Json]);
+            let data = lifetime_or_exit(&open(&dir));
+            let content = serde_json::to_string_pretty(&data).expect("JSON values are always serializable") + "\n";
+            write_output(path, content, output, "Lifetime export");
+        }
     }
+}
 
-    println!("\n---------- COMMENTS \u{1F4AC} ----------");
-    println!("You've posted {} comments", statistics.comments);
-    if let Some(info) = statistics.comment_info {
-        println!("  - First comment: \"{}\" ({})", info.first.content, info.first.date);
-        println!("  - Last comment: \"{}\" ({})", info.last.content, info.last.date);
+fn lifetime_or_exit(archive: &Archive) -> Value {
+    match archive.lifetime() {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            eprintln!("\u{274C} ERROR: The archive is empty, add exports with \"archive add\"");
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("\u{274C} ERROR: {err}");
+            process::exit(1);
+        }
     }
+}
 
-    println!("\n---------- DIRECT MESSAGES \u{2709} ----------");
-    for entry in statistics.dms.iter() {
-        println!("{} {} messages", entry.0, entry.1);
+fn timeline(input: &Input, by: Step, output: &Output) {
+    let format = output.format("timeline", &[Format::Text, Format::Json, Format::Csv]);
+    let mut data = load(&input.file, output);
+    let options = analysis_options(&input.options, &data, &input.file);
+    options.window.apply(&mut data);
+    let step = match by {
+        Step::Day => TimelineStep::Day,
+        Step::Month => TimelineStep::Month,
+    };
+    let timeline = build_timeline(&data, &options.timezone, step);
+
+    match format {
+        Format::Json => print_json(&timeline),
+        Format::Csv => {
+            let names: BTreeSet<&str> = timeline
+                .iter()
+                .flat_map(|period| period.sections.keys().map(String::as_str))
+                .collect();
+            let mut header = vec!["period", "total"];
+            header.extend(names.iter());
+            let rows: Vec<Vec<String>> = timeline
+                .iter()
+                .map(|period| {
+                    let mut row = vec![period.period.clone(), period.total.to_string()];
+                    row.extend(names.iter().map(|name| period.sections.get(*name).unwrap_or(&0).to_string()));
+                    row
+                })
+                .collect();
+            print!("{}", to_csv(&header, &rows));
+        }
+        Format::Text => {
+            output.heading("ACTIVITY TIMELINE \u{1F4C5}");
+            if timeline.is_empty() {
+                println!("No dated activity was found in the export");
+            }
+            for period in timeline.iter() {
+                let mut sections: Vec<(&String, &usize)> = period.sections.iter().collect();
+                sections.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                let details: Vec<String> = sections
+                    .iter()
+                    .take(3)
+                    .map(|(name, count)| format!("{} {}", name, count))
+                    .collect();
+                println!("{} : {} events ({})", period.period, period.total, details.join(", "));
+            }
+        }
     }
-    println!(
-        "\nTotal number of messages : {}",
-        statistics.dms.values().copied().sum::<usize>()
-    );
-    chart_utils::print_dms_chart(&statistics.dms);
-    if let Some(info) = statistics.dm_info {
-        println!("  - First message: \"{}\" ({})", info.first.content, info.first.date);
-        println!("  - Last message: \"{}\" ({})", info.last.content, info.last.date);
+}
+
+fn generate(options: GeneratorOptions, path: Option<PathBuf>, output: &Output) {
+    output.format("generate", &[Format::Json]);
+    let export = generate_export(&options);
+    let content = serde_json::to_string_pretty(&export).expect("JSON values are always serializable") + "\n";
+    write_output(path, content, output, "Generated export");
+}
+
+#[derive(Serialize)]
+struct Validation {
+    username: Option<String>,
+    sections: usize,
+    missing_sections: Vec<&'static str>,
+    unparsed_dates: Vec<UnparsedDates>,
+}
+
+// Errors stop the program with exit code 2, missing sections and unreadable dates are only reported
+fn validate(file: &str, output: &Output) {
+    let format = output.format("validate", &[Format::Text, Format::Json]);
+    let data = load(file, output);
+    let validation = Validation {
+        username: profile_info(&data)["userName"].as_str().map(String::from),
+        sections: data.as_object().map(|sections| sections.len()).unwrap_or(0),
+        missing_sections: missing_sections(&data),
+        unparsed_dates: find_unparsed_dates(&data),
+    };
+
+    if format == Format::Json {
+        print_json(&validation);
+        return;
+    }
+    output.heading("VALIDATION \u{1F50E}");
+    println!("\u{2705} The file can be analyzed");
+    match &validation.username {
+        Some(username) => println!("- Account : {}", username),
+        None => output.warning("No userName was found in Profile Info"),
     }
+    println!("- {} top-level sections", validation.sections);
+    for path in validation.missing_sections.iter() {
+        println!("\u{2796} Not in this export : {}", path);
+    }
+    for unparsed in validation.unparsed_dates.iter() {
+        output.warning(&format!(
+            "{} dates couldn't be read in {} (e.g. \"{}\")",
+            unparsed.count, unparsed.path, unparsed.example
+        ));
+    }
+}
 
-    println!("\n---------- REPOSTS \u{1F504} ----------");
-    println!("You've shared {} videos", statistics.shares);
+// Writes to the file given with --output, or to the standard output
+fn write_output(path: Option<PathBuf>, content: String, output: &Output, what: &str) {
+    match path {
+        Some(path) => {
+            std::fs::write(&path, content).unwrap_or_else(|err| {
+                eprintln!("\u{274C} ERROR: Could not write {} - {err}", path.display());
+                process::exit(1);
+            });
+            if !output.quiet {
+                println!("\u{2705} {} written to {}", what, path.display());
+            }
+        }
+        None => print!("{}", content),
+    }
+}
 
-    println!("\n---------- HASHTAGS \u{1F516} ----------");
+fn print_json<T: Serialize>(value: &T) {
     println!(
-        "You've viewed content from {} different hashtags",
-        statistics.hashtags_viewed
+        "{}",
+        serde_json::to_string_pretty(value).expect("results are always serializable")
     );
+}
 
-    println!("\n---------- AUDIENCE STATISTICS \u{1F464} ----------");
+// Reads and parses the export, and stops the program if it can't be analyzed
+fn load(file_path: &str, output: &Output) -> Value {
+    let file = read_file::file_into_str(file_path);
+    output.progress("\u{2705} File read successfully!");
 
-    if statistics.likes_received > 0 && statistics.videos_published == 0 {
-        println!("You've received {} likes but no videos were found in the data. You must have deleted old videos", statistics.likes_received);
-    } else if statistics.likes_received == 0 && statistics.videos_published == 0 {
-        println!("You haven't posted any videos or received any likes");
-    } else if statistics.likes_received == 0 && statistics.videos_published > 0 {
-        println!(
-            "You've published {} videos - sadly no one liked them",
-            statistics.videos_published
-        );
-    } else {
-        println!(
-            "You've received {} likes with {} videos",
-            statistics.likes_received, statistics.videos_published
+    let data = read_file::str_into_object(file).unwrap_or_else(|err| {
+        eprintln!(
+            "\u{274C} ERROR when converting file into object : {err}\nFile is most likely invalid."
         );
+        process::exit(2);
+    });
+    check_data_validity(&data);
+    data
+}
+
+fn analysis_options(args: &AnalysisArgs, data: &Value, file_path: &str) -> AnalysisOptions {
+    let timezone = match &args.timezone {
+        Some(zone) => UserTimeZone::parse(zone).unwrap_or_else(|err| {
+            eprintln!("\u{274C} ERROR: Invalid time zone - {err}");
+            eprintln!("Use an IANA name (Europe/Paris, America/New_York, UTC) or an offset (+02:00, -05:30).");
+            process::exit(1);
+        }),
+        None => UserTimeZone::Local,
+    };
+    let window = AnalysisWindow::parse(args.since.as_deref(), args.until.as_deref(), data, &timezone)
+        .unwrap_or_else(|err| {
+            eprintln!("\u{274C} ERROR: Invalid period - {err}");
+            eprintln!("Use a year (2024), a month (2024-03), a day (2024-03-15) or a duration (30d, 4w, 6m, 1y).");
+            process::exit(1);
+        });
+    let reference = match args.reference.as_str() {
+        "export" => ReferenceDate::export_date(data, Path::new(file_path)).unwrap_or_else(|err| {
+            eprintln!("\u{274C} ERROR: Invalid reference date - {err}");
+            eprintln!("Use --reference <date> instead.");
+            process::exit(1);
+        }),
+        date => ReferenceDate::parse(date, &timezone).unwrap_or_else(|err| {
+            eprintln!("\u{274C} ERROR: Invalid reference date - {err}");
+            eprintln!("Use \"latest\", \"export\", a day (2024-03-15) or a full date (2024-03-15 18:00:00).");
+            process::exit(1);
+        }),
+    };
+
+    AnalysisOptions {
+        window,
+        timezone,
+        reference,
+        decimals: args.decimals,
+    }
+}
+
+fn print_inventory(data: &Value, format: Format, output: &Output) {
+    let inventory = build_inventory(data);
+    match format {
+        Format::Json => {
+            print_json(&inventory);
+            return;
+        }
+        Format::Csv => {
+            let rows: Vec<Vec<String>> = inventory
+                .iter()
+                .map(|entry| {
+                    vec![
+                        entry.path.clone(),
+                        entry.depth.to_string(),
+                        entry.items.to_string(),
+                        entry.first_date.clone().unwrap_or_default(),
+                        entry.last_date.clone().unwrap_or_default(),
+                        entry.used.to_string(),
+                    ]
+                })
+                .collect();
+            print!("{}", to_csv(&["path", "depth", "items", "first_date", "last_date", "used"], &rows));
+            return;
+        }
+        Format::Text => {}
+    }
+
+    output.progress("\u{2705} Data seems valid!");
+    output.heading("EXPORT INVENTORY \u{1F5C2}");
+    println!("\u{2705} = used by the analyzer, \u{2796} = ignored\n");
+    for entry in inventory.iter() {
+        let name = entry.path.rsplit(" > ").next().unwrap_or(&entry.path);
+        let mark = if entry.used { "\u{2705}" } else { "\u{2796}" };
+        let dates = match (&entry.first_date, &entry.last_date) {
+            (Some(first), Some(last)) => format!(" ({} -> {})", first, last),
+            _ => String::new(),
+        };
         println!(
-            "You got {} likes per video on average",
-            (statistics.likes_received / statistics.videos_published)
+            "{}{} {} : {} items{}",
+            "    ".repeat(entry.depth),
+            mark,
+            name,
+            entry.items,
+            dates
         );
     }
-    println!("Note that likes from old videos are still counted.")
+
+    let sections = inventory.iter().filter(|entry| entry.depth == 0);
+    let (used, ignored): (Vec<_>, Vec<_>) = sections.partition(|entry| entry.used);
+    println!(
+        "\n{} sections are used by the analyzer, {} are ignored",
+        used.len(),
+        ignored.len()
+    );
 }
 
 fn check_data_validity(data: &Value) {
-    let profile_info = &data["Profile"]["Profile Info"];
     // To check the validity of the file, we are checking if we can find the Profile Info value.
     // If we can't, then the file is not valid
-    if profile_info.is_null() {
+    if profile_info(data).is_null() {
         eprintln!("\u{274C} ERROR: File is readable but doesn't seem to be valid data!");
-        eprintln!("Could not find the 'Profile Info' section within the 'Profile' section.");
+        eprintln!("Could not find the 'Profile Info' or 'Profile Information' section within the 'Profile' section.");
         eprintln!("This is necessary to retrieve basic user information.");
         process::exit(2);
     }
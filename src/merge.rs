@@ -0,0 +1,139 @@
+// merge.rs
+// merge.rs is in charge of :
+// - combining several exports of the same account into one, as TikTok only keeps a limited history
+// - removing the records found in more than one export, so every event is only counted once
+// The result has the layout of a regular export and can be analyzed like a single file
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::{date_utils, profile_info, record_date, str_field, window::latest_activity};
+
+// Keys holding the link of a record, whatever the section
+const LINK_KEYS: [&str; 6] = ["Link", "link", "VideoLink", "EffectLink", "SoundLink", "HashtagLink"];
+
+// Maps holding one record per entry, keyed by the ID of the live or of the order
+const RECORD_MAPS: [&str; 2] = ["WatchLiveMap", "OrderHistories"];
+
+// The most recent export is kept as it is and the records it lost are added back from the older ones.
+// Profile fields and other single values come from the most recent export
+pub fn merge_exports(exports: Vec<Value>) -> Result<Value, String> {
+    let usernames: HashSet<&str> = exports
+        .iter()
+        .filter_map(|export| profile_info(export)["userName"].as_str())
+        .collect();
+    if usernames.len() > 1 {
+        let mut usernames: Vec<&str> = usernames.into_iter().collect();
+        usernames.sort();
+        return Err(format!("the exports belong to different accounts ({})", usernames.join(", ")));
+    }
+
+    let mut exports = exports;
+    // Newest first
+    exports.sort_by_key(|export| std::cmp::Reverse(latest_activity(export)));
+    merge_newest_first(exports).ok_or_else(|| String::from("no export to merge"))
+}
+
+// Merges exports already ordered from the most recent to the oldest
+pub(crate) fn merge_newest_first(exports: Vec<Value>) -> Option<Value> {
+    let mut exports = exports.into_iter();
+    let mut merged = exports.next()?;
+    for older in exports {
+        merge_value(&mut merged, older);
+    }
+    Some(merged)
+}
+
+fn merge_value(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Object(base), Value::Object(other)) => {
+            for (key, value) in other {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(other)) => merge_records(base, other),
+        // The value of the most recent export wins
+        _ => {}
+    }
+}
+
+fn merge_records(base: &mut Vec<Value>, other: Vec<Value>) {
+    // Lists of plain values (like ad interests) are taken from the most recent export
+    if !base.iter().chain(other.iter()).all(|record| record.is_object()) {
+        return;
+    }
+
+    // Most lists are newest first, but some (comments, chat histories) are oldest first
+    let oldest_first = oldest_first(base).or_else(|| oldest_first(&other)).unwrap_or(false);
+
+    let mut seen: HashSet<String> = base.iter().map(record_key).collect();
+    let before = base.len();
+    for record in other {
+        if seen.insert(record_key(&record)) {
+            base.push(record);
+        }
+    }
+
+    // The merged list keeps the order of the most recent export when every record is dated
+    if base.len() > before && base.iter().all(|record| timestamp(record).is_some()) {
+        if oldest_first {
+            base.sort_by_key(timestamp);
+        } else {
+            base.sort_by_key(|record| std::cmp::Reverse(timestamp(record)));
+        }
+    }
+}
+
+// None when the list doesn't tell, because it has less than two different dates
+fn oldest_first(records: &[Value]) -> Option<bool> {
+    let first = records.iter().find_map(timestamp)?;
+    let last = records.iter().rev().find_map(timestamp)?;
+    (first != last).then_some(first < last)
+}
+
+// Removes from `export` every record `known` already holds, and returns how many records are left.
+// Single values are kept, so that the profile of the most recent export is not lost
+pub(crate) fn remove_known_records(known: &Value, export: &mut Value) -> usize {
+    match export {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, value)| match value {
+                // The entries of a record map are the same record when they share their ID
+                Value::Object(entries) if RECORD_MAPS.contains(&key.as_str()) => {
+                    let known = &known[key.as_str()];
+                    entries.retain(|id, _| known.get(id).is_none());
+                    entries.len()
+                }
+                value => remove_known_records(&known[key.as_str()], value),
+            })
+            .sum(),
+        Value::Array(records) if records.iter().all(|record| record.is_object()) => {
+            let seen: HashSet<String> = known
+                .as_array()
+                .map(|known| known.iter().map(record_key).collect())
+                .unwrap_or_default();
+            records.retain(|record| !seen.contains(&record_key(record)));
+            records.len()
+        }
+        _ => 0,
+    }
+}
+
+fn timestamp(record: &Value) -> Option<i64> {
+    record_date(record).and_then(|date| date_utils::date_to_unix_timestamp(&date))
+}
+
+// Records are the same event when they share their date and link. Records without a link
+// (logins, messages...) must be identical, so that two messages sent in the same second are both kept
+fn record_key(record: &Value) -> String {
+    let date = record_date(record).unwrap_or_default();
+    match str_field(record, &LINK_KEYS) {
+        Some(link) => format!("{}|{}", date, link),
+        None => format!("{}|{}", date, record),
+    }
+}
@@ -0,0 +1,116 @@
+// purchases.rs
+// purchases.rs is in charge of :
+// - reading the coin purchases, the gifts sent and the TikTok Shop order history
+// - adding up the money spent per currency and per month, and finding who received the most gifts
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{date_utils, record_date, str_field, UserTimeZone};
+
+#[derive(Serialize)]
+pub struct PurchaseStats {
+    pub coin_purchases: usize,
+    pub orders: usize,
+    pub spend_by_currency: BTreeMap<String, f64>,
+    pub spend_per_month: BTreeMap<String, BTreeMap<String, f64>>,
+    pub gifts_sent: usize,
+    pub gift_coins_sent: u64,
+    pub gift_recipients: HashMap<String, u64>,
+}
+
+pub fn purchase_stats(data: &Value, tz: &UserTimeZone) -> PurchaseStats {
+    let purchases = &data["Your Activity"]["Purchases"];
+    let coin_purchases = entries(&purchases["BuyGifts"]["BuyGifts"]);
+    let orders = entries(&data["Tiktok Shopping"]["Order History"]["OrderHistories"]);
+    let gifts = entries(&purchases["SendGifts"]["SendGifts"]);
+
+    let mut spend_by_currency = BTreeMap::new();
+    let mut spend_per_month: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+
+    let payments = coin_purchases
+        .iter()
+        .map(|entry| (entry, ["Price", "Amount", "price"]))
+        .chain(orders.iter().map(|entry| (entry, ["total_price", "Total", "Price"])));
+    for (entry, amount_keys) in payments {
+        let Some((amount, currency)) = str_field(entry, &amount_keys).and_then(parse_amount) else {
+            continue;
+        };
+        *spend_by_currency.entry(currency.clone()).or_insert(0.0) += amount;
+
+        if let Some(month) = record_date(entry).and_then(|date| date_utils::month_key(&date, tz)) {
+            *spend_per_month.entry(month).or_default().entry(currency).or_insert(0.0) += amount;
+        }
+    }
+
+    let mut gift_recipients = HashMap::new();
+    let mut gift_coins_sent = 0;
+    for gift in gifts.iter() {
+        let coins = match gift.get("GiftAmount") {
+            Some(Value::Number(coins)) => coins.as_u64().unwrap_or(0),
+            Some(Value::String(coins)) => coins.trim().parse::<u64>().unwrap_or(0),
+            _ => 0,
+        };
+        gift_coins_sent += coins;
+        let recipient = str_field(gift, &["UserName", "Username", "Recipient"]).unwrap_or("Unknown");
+        *gift_recipients.entry(recipient.to_string()).or_insert(0) += coins;
+    }
+
+    PurchaseStats {
+        coin_purchases: coin_purchases.len(),
+        orders: orders.len(),
+        spend_by_currency,
+        spend_per_month,
+        gifts_sent: gifts.len(),
+        gift_coins_sent,
+        gift_recipients,
+    }
+}
+
+// Orders are stored in a map keyed by order ID, the other lists are arrays
+fn entries(list: &Value) -> Vec<&Value> {
+    match list {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Prices come as "0.99 USD", "USD 0.99", "$0.99" or "1,99 €"
+fn parse_amount(price: &str) -> Option<(f64, String)> {
+    let number: String = price
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+    // The last separator is the decimal one when both kinds are used ("1,234.56", "1.234,56") or when
+    // it is followed by 1 or 2 digits ("1,99"). Otherwise separators split thousands ("$1,234", "1.000 €")
+    let number = match number.rfind(['.', ',']) {
+        Some(decimal) if is_decimal_separator(&number, decimal) => {
+            format!("{}.{}", number[..decimal].replace(['.', ','], ""), &number[decimal + 1..])
+        }
+        _ => number.replace(['.', ','], ""),
+    };
+    let amount = number.parse::<f64>().ok()?;
+
+    let code: String = price.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    let currency = if code.len() == 3 {
+        code.to_uppercase()
+    } else if price.contains('$') {
+        String::from("USD")
+    } else if price.contains('€') {
+        String::from("EUR")
+    } else if price.contains('£') {
+        String::from("GBP")
+    } else {
+        String::from("Unknown")
+    };
+
+    Some((amount, currency))
+}
+
+fn is_decimal_separator(number: &str, separator: usize) -> bool {
+    let both_used = number.contains('.') && number.contains(',');
+    let decimals = number.len() - separator - 1;
+    both_used || (1..=2).contains(&decimals)
+}
@@ -0,0 +1,128 @@
+// rates.rs
+// rates.rs is in charge of :
+// - choosing the reference date ("now") that the averages are measured against
+// - turning a number of events and the date of the oldest one into an average per day,
+// along with the period that average covers
+
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::window::latest_activity;
+use crate::{date_utils, record_date, AnalysisWindow, UserTimeZone};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReferenceDate {
+    // The most recent dated record of the export, whatever its section
+    #[default]
+    LatestEvent,
+    Fixed(i64),
+    // The date the export was made, in seconds since the epoch. TikTok doesn't write it inside the file,
+    // so it is the latest dated record of the whole export, before the analysis window cuts it
+    ExportDate(i64),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Rate {
+    pub count: usize,
+    pub per_day: f64,
+    pub days: usize,
+    pub from: Option<String>,
+    pub to: String,
+}
+
+impl ReferenceDate {
+    // Accepts "latest", a day ("2024-03-15") or a full date ("2024-03-15 18:00:00") read in the user's time zone.
+    // A day means the end of that day. "export" needs the export itself, it is read by `ReferenceDate::export_date`
+    pub fn parse(reference: &str, tz: &UserTimeZone) -> Result<ReferenceDate, String> {
+        let reference = reference.trim();
+        if reference.eq_ignore_ascii_case("latest") {
+            return Ok(ReferenceDate::LatestEvent);
+        }
+
+        let local = NaiveDateTime::parse_from_str(reference, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(reference, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|day| day.and_hms_opt(23, 59, 59))
+            });
+        local
+            .and_then(|local| tz.timestamp_of_local(local))
+            .map(ReferenceDate::Fixed)
+            .ok_or_else(|| format!("\"{}\" is not a valid reference date", reference))
+    }
+
+    // The modification time of the file only stands in for exports without any readable date, as copying
+    // or unzipping the export changes it
+    pub fn export_date(data: &Value, file_path: &Path) -> Result<ReferenceDate, String> {
+        let latest = latest_activity(data);
+        if latest > 0 {
+            return Ok(ReferenceDate::ExportDate(latest));
+        }
+        std::fs::metadata(file_path)
+            .ok()
+            .filter(|metadata| metadata.is_file())
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|since_epoch| ReferenceDate::ExportDate(since_epoch.as_secs() as i64))
+            .ok_or_else(|| format!("{} holds no date and the date of the file couldn't be read", file_path.display()))
+    }
+
+    // The reference never goes past the end of the analysis window
+    pub(crate) fn resolve(&self, data: &Value, window: &AnalysisWindow) -> i64 {
+        let reference = match self {
+            ReferenceDate::LatestEvent => latest_activity(data),
+            ReferenceDate::Fixed(timestamp) | ReferenceDate::ExportDate(timestamp) => *timestamp,
+        };
+        match window.until {
+            Some(until) => reference.min(until),
+            None => reference,
+        }
+    }
+}
+
+// Average per day of a list of records, from its oldest readable date to the reference date.
+// Records dated after the reference are left out, undated records are still counted
+pub(crate) fn list_rate(list: &Value, reference: i64, tz: &UserTimeZone, decimals: usize) -> Rate {
+    let dates: Vec<Option<i64>> = list
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|record| record_date(record).and_then(|date| date_utils::date_to_unix_timestamp(&date)))
+        .collect();
+    let count = dates.iter().filter(|date| date.is_none_or(|date| date <= reference)).count();
+    let oldest = dates.iter().flatten().filter(|date| **date <= reference).min().copied();
+    rate(count, oldest, reference, tz, decimals)
+}
+
+// `oldest` is the timestamp of the oldest event, never after the reference. When every event happened
+// on the reference day, the average is the number of events of that day
+fn rate(count: usize, oldest: Option<i64>, reference: i64, tz: &UserTimeZone, decimals: usize) -> Rate {
+    let format_day = |timestamp: i64| tz.local_date_time(timestamp).format("%Y-%m-%d").to_string();
+
+    let days = oldest
+        .map(|oldest| {
+            let reference_day = tz.local_date_time(reference).date();
+            (reference_day - tz.local_date_time(oldest).date()).num_days() as usize
+        })
+        .unwrap_or(0);
+
+    let per_day = count as f64 / days.max(1) as f64;
+
+    Rate {
+        count,
+        per_day: round(per_day, decimals),
+        days,
+        from: oldest.map(format_day),
+        to: format_day(reference),
+    }
+}
+
+pub(crate) fn round(value: f64, decimals: usize) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
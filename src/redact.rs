@@ -0,0 +1,165 @@
+// redact.rs
+// redact.rs is in charge of :
+// - masking message content, the names of the people the account talks to, links, precise locations
+// and profile details in the results, so that reports can be shown or screenshotted without exposing anyone
+// Counts, dates and rates are kept, names are replaced by "Contact 1", "Contact 2"... in every output format
+
+use std::collections::HashMap;
+
+use crate::{Comparison, DateInfo, ExportDiff, Statistics};
+
+const HIDDEN_MESSAGE: &str = "[message hidden]";
+const HIDDEN_LINK: &str = "[link hidden]";
+const HIDDEN_VALUE: &str = "[value hidden]";
+
+// The same person gets the same label everywhere in the results
+#[derive(Default)]
+struct Contacts {
+    labels: HashMap<String, String>,
+}
+
+impl Contacts {
+    fn label(&mut self, name: &str) -> String {
+        if name.is_empty() {
+            return String::new();
+        }
+        let next = self.labels.len() + 1;
+        self.labels
+            .entry(name.to_string())
+            .or_insert_with(|| format!("Contact {}", next))
+            .clone()
+    }
+
+    // Labels are given from the biggest count down, so that "Contact 1" is the closest contact
+    // `split` cuts a key around the name it holds, into (before, name, after)
+    fn relabel<V: Copy + Ord>(
+        &mut self,
+        counts: &HashMap<String, V>,
+        split: impl Fn(&str) -> (&str, &str, &str),
+    ) -> HashMap<String, V> {
+        let mut entries: Vec<(&String, &V)> = counts.iter().collect();
+        entries.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        entries
+            .into_iter()
+            .map(|(key, value)| {
+                let (before, name, after) = split(key);
+                (format!("{}{}{}", before, self.label(name), after), *value)
+            })
+            .collect()
+    }
+}
+
+impl Statistics {
+    pub fn redact(&mut self) {
+        let mut contacts = Contacts::default();
+
+        // Keys look like "Chat with bob:"
+        self.dms = contacts.relabel(&self.dms, |chat| {
+            match chat.strip_prefix("Chat with ").and_then(|name| name.strip_suffix(':')) {
+                Some(name) => ("Chat with ", name, ":"),
+                None => ("", chat, ""),
+            }
+        });
+        if let Some(info) = self.dm_info.as_mut() {
+            for item in [&mut info.first, &mut info.last] {
+                // "(in Chat History with bob:) hello"
+                let chat = item
+                    .content
+                    .strip_prefix("(in Chat History with ")
+                    .and_then(|rest| rest.split_once(":) "))
+                    .map(|(name, _)| name.to_string());
+                item.content = match chat {
+                    Some(name) => format!("(in Chat History with {}:) {}", contacts.label(&name), HIDDEN_MESSAGE),
+                    None => String::from(HIDDEN_MESSAGE),
+                };
+            }
+        }
+        // Paths like "Direct Message > Direct Messages > ChatHistory > Chat History with bob:"
+        for unparsed in self.unparsed_dates.iter_mut() {
+            let segments: Vec<String> = unparsed
+                .path
+                .split(" > ")
+                .map(|segment| {
+                    match segment.strip_prefix("Chat History with ").and_then(|name| name.strip_suffix(':')) {
+                        Some(name) => format!("Chat History with {}:", contacts.label(name)),
+                        None => segment.to_string(),
+                    }
+                })
+                .collect();
+            unparsed.path = segments.join(" > ");
+        }
+        hide(&mut self.comment_info, HIDDEN_MESSAGE);
+
+        let follows = &mut self.follows;
+        for info in [&mut follows.follower_info, &mut follows.following_info].into_iter().flatten() {
+            info.first.content = contacts.label(&info.first.content);
+            info.last.content = contacts.label(&info.last.content);
+        }
+        follows.mutuals = follows.mutuals.iter().map(|name| contacts.label(name)).collect();
+        follows.mutuals.sort();
+
+        let live = &mut self.live;
+        live.hosts = contacts.relabel(&live.hosts, |host| ("", host, ""));
+        if let Some(info) = live.watch_info.as_mut() {
+            info.first.content = contacts.label(&info.first.content);
+            info.last.content = contacts.label(&info.last.content);
+        }
+        hide(&mut live.comment_info, HIDDEN_MESSAGE);
+        self.purchases.gift_recipients = contacts.relabel(&self.purchases.gift_recipients, |name| ("", name, ""));
+
+        for info in [&mut self.like_info, &mut self.watch_info, &mut self.shares.share_info] {
+            hide(info, HIDDEN_LINK);
+        }
+        let favorites = &mut self.favorite_info;
+        for category in [&mut favorites.sounds, &mut favorites.effects, &mut favorites.videos, &mut favorites.hashtags] {
+            hide(&mut category.info, HIDDEN_LINK);
+        }
+        for video in self.rewatches.top.iter_mut() {
+            video.link = String::from(HIDDEN_LINK);
+            video.video_id = String::from(HIDDEN_LINK);
+        }
+
+        // Precise values (IP addresses, GPS data, addresses...) become "Location 1", "Location 2"...
+        // so that the number of different places is still shown
+        for field in self.location.fields.iter_mut().filter(|field| field.flagged) {
+            for (index, location) in field.values.iter_mut().enumerate() {
+                location.value = format!("Location {}", index + 1);
+            }
+        }
+    }
+}
+
+impl ExportDiff {
+    pub fn redact(&mut self) {
+        let mut contacts = Contacts::default();
+        for names in [&mut self.new_followers, &mut self.unfollowers, &mut self.new_following, &mut self.unfollowed] {
+            *names = names.iter().map(|name| contacts.label(name)).collect();
+        }
+        for post in self.deleted_posts.iter_mut() {
+            post.link = String::from(HIDDEN_LINK);
+        }
+        // The profile holds the email address, the phone number and the bio
+        for change in self.profile_changes.iter_mut() {
+            for value in [&mut change.old, &mut change.new].into_iter().flatten() {
+                *value = String::from(HIDDEN_VALUE);
+            }
+        }
+    }
+}
+
+impl Comparison {
+    // The compared accounts belong to other people, they become "Account 1", "Account 2"... in the order of the rows
+    pub fn redact(&mut self) {
+        for (index, row) in self.accounts.iter_mut().enumerate() {
+            row.account = format!("Account {}", index + 1);
+            row.username = row.account.clone();
+        }
+    }
+}
+
+fn hide(info: &mut Option<DateInfo>, placeholder: &str) {
+    if let Some(info) = info.as_mut() {
+        info.first.content = placeholder.to_string();
+        info.last.content = placeholder.to_string();
+    }
+}
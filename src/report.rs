@@ -0,0 +1,886 @@
+// report.rs
+// report.rs is in charge of :
+// - writing the statistics as the text report, one section at a time
+// - letting the user pick which sections are written, and in which order
+// Everything is written to a fmt::Write (usually a String) instead of the console, so reports can be tested
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+
+use crate::{charts, Rate, Statistics, AVERAGE_VIDEO_SECONDS};
+
+// One part of the report, like "LOGINS" or "DIRECT MESSAGES"
+pub trait ReportSection {
+    // Name used to pick the section on the command line
+    fn name(&self) -> &'static str;
+
+    fn title(&self) -> &'static str;
+
+    // Fields of Statistics shown by the section, used to filter the JSON output the same way
+    fn fields(&self) -> &'static [&'static str];
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result;
+}
+
+// Fields of Statistics that describe the whole report rather than one section
+const HEADER_FIELDS: [&str; 5] = ["username", "period", "timezone", "reference_date", "unparsed_dates"];
+
+// Every section, in the default order of the report
+pub fn all_sections() -> Vec<Box<dyn ReportSection>> {
+    vec![
+        Box::new(Logins),
+        Box::new(VideoConsumption),
+        Box::new(Rewatches),
+        Box::new(TimeSpent),
+        Box::new(Favorites),
+        Box::new(Likes),
+        Box::new(Funnel),
+        Box::new(Comments),
+        Box::new(DirectMessages),
+        Box::new(Follows),
+        Box::new(Live),
+        Box::new(Purchases),
+        Box::new(Reposts),
+        Box::new(Hashtags),
+        Box::new(Ads),
+        Box::new(Location),
+        Box::new(Audience),
+    ]
+}
+
+// Section names are read in order: "dms,logins" only writes these two sections, DMs first.
+// A name starting with "-" removes a section, "-location" writes every section but the location data.
+// "..." stands for every section that isn't named, "dms,..." moves the DMs to the top
+pub fn select_sections(names: &[String]) -> Result<Vec<Box<dyn ReportSection>>, String> {
+    let mut included: Vec<Option<&'static str>> = Vec::new();
+    let mut excluded: Vec<&'static str> = Vec::new();
+    let mut rest = false;
+
+    for name in names.iter().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+        if name == "..." {
+            included.push(None);
+            rest = true;
+            continue;
+        }
+        let (remove, name) = match name.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, name),
+        };
+        let key = section_key(name)?;
+        if remove {
+            excluded.push(key);
+        } else if !included.contains(&Some(key)) {
+            included.push(Some(key));
+        }
+    }
+    // Only removing sections keeps all the others
+    if included.is_empty() {
+        included.push(None);
+        rest = true;
+    }
+
+    let named: Vec<&'static str> = included.iter().flatten().copied().collect();
+    let mut sections: Vec<Box<dyn ReportSection>> = Vec::new();
+    for entry in included {
+        let chosen: Vec<Box<dyn ReportSection>> = all_sections()
+            .into_iter()
+            .filter(|section| match entry {
+                Some(key) => section.name() == key,
+                None => rest && !named.contains(&section.name()),
+            })
+            .collect();
+        sections.extend(chosen);
+    }
+    sections.retain(|section| !excluded.contains(&section.name()));
+    Ok(sections)
+}
+
+fn section_key(name: &str) -> Result<&'static str, String> {
+    all_sections()
+        .iter()
+        .map(|section| section.name())
+        .find(|key| key.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let keys: Vec<&str> = all_sections().iter().map(|section| section.name()).collect();
+            format!("unknown section \"{}\", the sections are {}", name, keys.join(", "))
+        })
+}
+
+// With `color`, headings are written in bold cyan and warnings in yellow using ANSI codes
+pub fn render_report(statistics: &Statistics, sections: &[Box<dyn ReportSection>], color: bool) -> String {
+    let mut out = String::new();
+    write_report(statistics, sections, color, &mut out).expect("writing to a String can't fail");
+    out
+}
+
+fn write_report(
+    statistics: &Statistics,
+    sections: &[Box<dyn ReportSection>],
+    color: bool,
+    out: &mut dyn Write,
+) -> fmt::Result {
+    writeln!(out, "The data of {} has been analyzed. Results :", statistics.username)?;
+    writeln!(out, "Period covered : {} ({})", statistics.period, statistics.timezone)?;
+    writeln!(out, "Averages are measured up to {}", statistics.reference_date)?;
+    for unparsed in statistics.unparsed_dates.iter() {
+        let warning = format!(
+            "\u{26A0} {} dates couldn't be read in {} (e.g. \"{}\"), these items are left out of the dated statistics",
+            unparsed.count, unparsed.path, unparsed.example
+        );
+        writeln!(out, "{}", paint(&warning, "33", color))?;
+    }
+
+    for section in sections {
+        let heading = format!("---------- {} ----------", section.title());
+        writeln!(out, "\n{}", paint(&heading, "1;36", color))?;
+        section.render(statistics, out)?;
+    }
+    Ok(())
+}
+
+pub fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+// The statistics as JSON, with only the fields of the chosen sections
+pub fn report_json(statistics: &Statistics, sections: &[Box<dyn ReportSection>]) -> Value {
+    let Value::Object(mut all) = serde_json::to_value(statistics).expect("statistics are always serializable") else {
+        unreachable!("Statistics is a struct");
+    };
+    let mut fields = serde_json::Map::new();
+    let chosen = HEADER_FIELDS
+        .iter()
+        .chain(sections.iter().flat_map(|section| section.fields().iter()));
+    for field in chosen {
+        if let Some(value) = all.remove(*field) {
+            fields.insert(field.to_string(), value);
+        }
+    }
+    Value::Object(fields)
+}
+
+// e.g. "(2024-01-01 -> 2024-03-31, 90 days)"
+fn rate_period(rate: &Rate) -> String {
+    match &rate.from {
+        Some(from) => format!("({} -> {}, {} days)", from, rate.to, rate.days),
+        None => String::from("(no dated records)"),
+    }
+}
+
+pub struct Logins;
+
+impl ReportSection for Logins {
+    fn name(&self) -> &'static str {
+        "logins"
+    }
+
+    fn title(&self) -> &'static str {
+        "LOGINS \u{1F511}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["logins"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        writeln!(
+            out,
+            "In the last {} days, you've launched TikTok {} times",
+            statistics.logins.days_since_first_login, statistics.logins.openings
+        )?;
+        writeln!(
+            out,
+            "- {} launches per day on average {}",
+            statistics.logins.rate.per_day,
+            rate_period(&statistics.logins.rate)
+        )?;
+        Ok(())
+    }
+}
+
+pub struct VideoConsumption;
+
+impl ReportSection for VideoConsumption {
+    fn name(&self) -> &'static str {
+        "videos"
+    }
+
+    fn title(&self) -> &'static str {
+        "VIDEO CONSUMPTION \u{1F4FA}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["watched", "watch_info"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        writeln!(
+            out,
+            "In the last {} days, you've watched {} videos",
+            statistics.watched.days_since_first_video, statistics.watched.videos_watched
+        )?;
+        writeln!(
+            out,
+            "- {} watched videos per day on average {}",
+            statistics.watched.rate.per_day,
+            rate_period(&statistics.watched.rate)
+        )?;
+        if let Some(info) = &statistics.watch_info {
+            writeln!(out, "  - First seen: {} ({})", info.first.content, info.first.raw_date)?;
+            writeln!(out, "  - Last seen: {} ({})", info.last.content, info.last.raw_date)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Rewatches;
+
+impl ReportSection for Rewatches {
+    fn name(&self) -> &'static str {
+        "rewatches"
+    }
+
+    fn title(&self) -> &'static str {
+        "REWATCHED VIDEOS \u{1F501}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["rewatches"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let rewatches = &statistics.rewatches;
+        writeln!(
+            out,
+            "You've watched {} videos more than once ({} extra views)",
+            rewatches.rewatched_videos, rewatches.extra_views
+        )?;
+        for video in rewatches.top.iter() {
+            writeln!(
+                out,
+                "  - {} : {} views over {} days ({} -> {})",
+                video.link, video.views, video.span_days, video.first_watched, video.last_watched
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct TimeSpent;
+
+impl ReportSection for TimeSpent {
+    fn name(&self) -> &'static str {
+        "time"
+    }
+
+    fn title(&self) -> &'static str {
+        "TIME SPENT DAILY \u{1F570}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["time"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        charts::time_spent_chart(out, (statistics.watched.rate.per_day * AVERAGE_VIDEO_SECONDS / 60.0) as usize)?;
+        writeln!(out, "TikTok videos are on average 27.5 seconds long, so we can estimate how much time you spend on TikTok every day.")?;
+        writeln!(out, "Time wasted on TikTok every day : {} on average", statistics.time)?;
+        writeln!(
+            out,
+            "This stat is not 100% precise! The more you tend to not watch whole videos, the more time you can remove."
+        )?;
+        Ok(())
+    }
+}
+
+pub struct Favorites;
+
+impl ReportSection for Favorites {
+    fn name(&self) -> &'static str {
+        "favorites"
+    }
+
+    fn title(&self) -> &'static str {
+        "FAVORITES \u{1F60D}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["favorites", "favorite_info"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        writeln!(out, "{} favorite effects", statistics.favorites.effects)?;
+        writeln!(out, "{} favorite hashtags", statistics.favorites.hashtags)?;
+        writeln!(out, "{} favorite sounds", statistics.favorites.sounds)?;
+        writeln!(out, "{} favorite videos", statistics.favorites.videos)?;
+
+        let favorite_info = &statistics.favorite_info;
+        let categories = [
+            ("sound", &favorite_info.sounds),
+            ("effect", &favorite_info.effects),
+            ("video", &favorite_info.videos),
+            ("hashtag", &favorite_info.hashtags),
+        ];
+        for (name, category) in categories {
+            if let Some(info) = &category.info {
+                writeln!(
+                    out,
+                    "  - First favorite {}: {} ({})",
+                    name, info.first.content, info.first.raw_date
+                )?;
+                writeln!(
+                    out,
+                    "  - Last favorite {}: {} ({})",
+                    name, info.last.content, info.last.raw_date
+                )?;
+            }
+        }
+        writeln!(
+            out,
+            "{} of your favorite videos ({:.1}%) are also in your liked videos",
+            favorite_info.videos_also_liked, favorite_info.videos_also_liked_percentage
+        )?;
+        charts::monthly_chart(out, "Favorites per Month", &favorite_info.per_month)?;
+        Ok(())
+    }
+}
+
+pub struct Likes;
+
+impl ReportSection for Likes {
+    fn name(&self) -> &'static str {
+        "likes"
+    }
+
+    fn title(&self) -> &'static str {
+        "LIKES \u{2764}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["likes_left", "like_info"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        writeln!(
+            out,
+            "You've liked {} videos in the last {} days",
+            statistics.likes_left.videos_liked, statistics.likes_left.days_since_oldest_like
+        )?;
+        writeln!(
+            out,
+            "You've liked {} videos per day on average {}\n",
+            statistics.likes_left.rate.per_day,
+            rate_period(&statistics.likes_left.rate)
+        )?;
+        writeln!(
+            out,
+            "You've liked {:.1}% of the videos you've watched\n",
+            statistics.likes_left.liked_percentage
+        )?;
+        if let Some(info) = &statistics.like_info {
+            writeln!(
+                out,
+                "  - First liked video: {} ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(
+                out,
+                "  - Last liked video: {} ({})",
+                info.last.content, info.last.raw_date
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Funnel;
+
+impl ReportSection for Funnel {
+    fn name(&self) -> &'static str {
+        "funnel"
+    }
+
+    fn title(&self) -> &'static str {
+        "ENGAGEMENT FUNNEL \u{1F53D}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["funnel"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let funnel = &statistics.funnel;
+        writeln!(out, "Out of {} different videos watched :", funnel.watched)?;
+        writeln!(out, "- {} were liked ({:.1}%)", funnel.liked, funnel.liked_percentage)?;
+        writeln!(
+            out,
+            "- {} were added to favorites ({:.1}%)",
+            funnel.favorited, funnel.favorited_percentage
+        )?;
+        writeln!(
+            out,
+            "- {} were shared ({:.1}%)",
+            funnel.shared, funnel.shared_percentage
+        )?;
+        writeln!(
+            out,
+            "Videos are matched on the ID in their link, so short links can't be counted."
+        )?;
+        Ok(())
+    }
+}
+
+pub struct Comments;
+
+impl ReportSection for Comments {
+    fn name(&self) -> &'static str {
+        "comments"
+    }
+
+    fn title(&self) -> &'static str {
+        "COMMENTS \u{1F4AC}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["comments", "comment_info"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        writeln!(out, "You've posted {} comments", statistics.comments)?;
+        if let Some(info) = &statistics.comment_info {
+            writeln!(
+                out,
+                "  - First comment: \"{}\" ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(
+                out,
+                "  - Last comment: \"{}\" ({})",
+                info.last.content, info.last.raw_date
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct DirectMessages;
+
+impl ReportSection for DirectMessages {
+    fn name(&self) -> &'static str {
+        "dms"
+    }
+
+    fn title(&self) -> &'static str {
+        "DIRECT MESSAGES \u{2709}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["dms", "dm_info"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        // Biggest conversations first, so the list reads the same on every run
+        let mut chats: Vec<(&String, &usize)> = statistics.dms.iter().collect();
+        chats.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (chat, count) in chats {
+            writeln!(out, "{} {} messages", chat, count)?;
+        }
+        writeln!(
+            out,
+            "\nTotal number of messages : {}",
+            statistics.dms.values().copied().sum::<usize>()
+        )?;
+        charts::dms_chart(out, &statistics.dms)?;
+        if let Some(info) = &statistics.dm_info {
+            writeln!(
+                out,
+                "  - First message: \"{}\" ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(
+                out,
+                "  - Last message: \"{}\" ({})",
+                info.last.content, info.last.raw_date
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Follows;
+
+impl ReportSection for Follows {
+    fn name(&self) -> &'static str {
+        "follows"
+    }
+
+    fn title(&self) -> &'static str {
+        "FOLLOWERS & FOLLOWING \u{1F465}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["follows"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let follows = &statistics.follows;
+        writeln!(
+            out,
+            "You have {} followers and you follow {} accounts",
+            follows.followers, follows.following
+        )?;
+        writeln!(out, "- {} of them are mutual follows", follows.mutuals.len())?;
+        if let Some(info) = &follows.follower_info {
+            writeln!(
+                out,
+                "  - Oldest follower: {} ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(
+                out,
+                "  - Newest follower: {} ({})",
+                info.last.content, info.last.raw_date
+            )?;
+        }
+        if let Some(info) = &follows.following_info {
+            writeln!(
+                out,
+                "  - Oldest follow: {} ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(out, "  - Newest follow: {} ({})", info.last.content, info.last.raw_date)?;
+        }
+        charts::follow_growth_chart(out, &follows.followers_per_month, &follows.following_per_month)?;
+        Ok(())
+    }
+}
+
+pub struct Live;
+
+impl ReportSection for Live {
+    fn name(&self) -> &'static str {
+        "live"
+    }
+
+    fn title(&self) -> &'static str {
+        "TIKTOK LIVE \u{1F534}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["live"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let live = &statistics.live;
+        writeln!(
+            out,
+            "You've watched {} lives from {} different hosts",
+            live.lives_watched,
+            live.hosts.len()
+        )?;
+        writeln!(out, "- {} comments posted during lives", live.comments)?;
+        if live.gifts_sent > 0 {
+            writeln!(out, "- {} gifts sent during lives", live.gifts_sent)?;
+        }
+        charts::distribution_chart(out, "Most Watched Hosts", &live.hosts)?;
+        if let Some(info) = &live.comment_info {
+            writeln!(
+                out,
+                "  - First live comment: \"{}\" ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(
+                out,
+                "  - Last live comment: \"{}\" ({})",
+                info.last.content, info.last.raw_date
+            )?;
+        }
+        if live.hosted.is_empty() {
+            writeln!(out, "You've never hosted a live")?;
+        } else {
+            writeln!(
+                out,
+                "You've hosted {} lives for {} hours and {} minutes in total",
+                live.hosted.len(),
+                live.hosted_minutes / 60,
+                live.hosted_minutes % 60
+            )?;
+            for hosted in live.hosted.iter() {
+                match hosted.duration_minutes {
+                    Some(minutes) => writeln!(out, "  - {} : {} minutes", hosted.date, minutes)?,
+                    None => writeln!(out, "  - {} : unknown duration", hosted.date)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Purchases;
+
+impl ReportSection for Purchases {
+    fn name(&self) -> &'static str {
+        "purchases"
+    }
+
+    fn title(&self) -> &'static str {
+        "PURCHASES & GIFTS \u{1F4B0}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["purchases"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let purchases = &statistics.purchases;
+        writeln!(
+            out,
+            "You've bought coins {} times and placed {} TikTok Shop orders",
+            purchases.coin_purchases, purchases.orders
+        )?;
+        if purchases.spend_by_currency.is_empty() {
+            writeln!(out, "No money spent on TikTok was found in the data")?;
+        }
+        for (currency, amount) in purchases.spend_by_currency.iter() {
+            writeln!(out, "- {:.2} {} spent in total", amount, currency)?;
+        }
+        // The chart only shows the currency the most money was spent in, as amounts can't be added across currencies
+        if let Some((main_currency, _)) = purchases.spend_by_currency.iter().max_by(|a, b| a.1.total_cmp(b.1)) {
+            let per_month: BTreeMap<String, f64> = purchases
+                .spend_per_month
+                .iter()
+                .filter_map(|(month, spend)| spend.get(main_currency).map(|amount| (month.clone(), *amount)))
+                .collect();
+            charts::monthly_amount_chart(out, &format!("Spend per Month ({})", main_currency), &per_month)?;
+        }
+        writeln!(
+            out,
+            "You've sent {} gifts worth {} coins",
+            purchases.gifts_sent, purchases.gift_coins_sent
+        )?;
+        let gift_recipients: HashMap<String, usize> = purchases
+            .gift_recipients
+            .iter()
+            .map(|(name, coins)| (name.clone(), *coins as usize))
+            .collect();
+        charts::distribution_chart(out, "Top Gift Recipients (coins)", &gift_recipients)?;
+        Ok(())
+    }
+}
+
+pub struct Reposts;
+
+impl ReportSection for Reposts {
+    fn name(&self) -> &'static str {
+        "reposts"
+    }
+
+    fn title(&self) -> &'static str {
+        "REPOSTS \u{1F504}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["shares"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let shares = &statistics.shares;
+        writeln!(out, "You've shared {} times", shares.total)?;
+        writeln!(
+            out,
+            "- {:.2} shares for every 100 videos you've watched",
+            shares.shares_per_watched_video * 100.0
+        )?;
+        for (month, count) in shares.per_month.iter() {
+            writeln!(out, "  {} : {} shares", month, count)?;
+        }
+        let mut content_types: Vec<(&String, &usize)> = shares.by_content.iter().collect();
+        content_types.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (content, count) in content_types {
+            writeln!(out, "- {} shares of {} content", count, content)?;
+        }
+        charts::distribution_chart(out, "Share Channels", &shares.by_method)?;
+        if let Some(info) = &shares.share_info {
+            writeln!(out, "  - First share: {} ({})", info.first.content, info.first.raw_date)?;
+            writeln!(out, "  - Last share: {} ({})", info.last.content, info.last.raw_date)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Hashtags;
+
+impl ReportSection for Hashtags {
+    fn name(&self) -> &'static str {
+        "hashtags"
+    }
+
+    fn title(&self) -> &'static str {
+        "HASHTAGS \u{1F516}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["hashtags_viewed"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        writeln!(
+            out,
+            "You've viewed content from {} different hashtags",
+            statistics.hashtags_viewed
+        )?;
+        Ok(())
+    }
+}
+
+pub struct Ads;
+
+impl ReportSection for Ads {
+    fn name(&self) -> &'static str {
+        "ads"
+    }
+
+    fn title(&self) -> &'static str {
+        "ADS & OFF-TIKTOK ACTIVITY \u{1F575}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["ads"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let ads = &statistics.ads;
+        writeln!(
+            out,
+            "{} apps and websites shared {} events about you with TikTok",
+            ads.sources.len(),
+            ads.off_tiktok_events
+        )?;
+        charts::distribution_chart(out, "Apps & Advertisers", &ads.sources)?;
+        charts::distribution_chart(out, "Event Types", &ads.event_types)?;
+        charts::monthly_chart(out, "Shared Events per Month", &ads.events_per_month)?;
+        if let Some(info) = &ads.event_info {
+            writeln!(
+                out,
+                "  - First shared event: {} ({})",
+                info.first.content, info.first.raw_date
+            )?;
+            writeln!(
+                out,
+                "  - Last shared event: {} ({})",
+                info.last.content, info.last.raw_date
+            )?;
+        }
+        if ads.ad_interests.is_empty() {
+            writeln!(out, "TikTok didn't export any ad interest for you")?;
+        } else {
+            writeln!(
+                out,
+                "TikTok thinks you're interested in : {}",
+                ads.ad_interests.join(", ")
+            )?;
+        }
+        writeln!(
+            out,
+            "You can turn off ad personalization in Settings and privacy -> Ads."
+        )?;
+        Ok(())
+    }
+}
+
+pub struct Location;
+
+impl ReportSection for Location {
+    fn name(&self) -> &'static str {
+        "location"
+    }
+
+    fn title(&self) -> &'static str {
+        "LOCATION DATA \u{1F4CD}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["location"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let location = &statistics.location;
+        if location.fields.is_empty() {
+            writeln!(out, "No location data was found in the export")?;
+        }
+        for field in location.fields.iter() {
+            let flag = if field.flagged { " \u{26A0}" } else { "" };
+            writeln!(out, "{}{} : {} different values", field.path, flag, field.values.len())?;
+            for value in field.values.iter().take(5) {
+                match (&value.first_seen, &value.last_seen) {
+                    (Some(first), Some(last)) => writeln!(
+                        out,
+                        "  - {} (seen {} times, {} -> {})",
+                        value.value, value.occurrences, first, last
+                    )?,
+                    _ => writeln!(out, "  - {} (seen {} times)", value.value, value.occurrences)?,
+                }
+            }
+            if field.values.len() > 5 {
+                writeln!(out, "  - ... and {} more", field.values.len() - 5)?;
+            }
+        }
+        if location.fields.iter().any(|field| field.flagged) {
+            writeln!(out, "\u{26A0} These fields are precise enough to locate you. You may want to delete them from your account.")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Audience;
+
+impl ReportSection for Audience {
+    fn name(&self) -> &'static str {
+        "audience"
+    }
+
+    fn title(&self) -> &'static str {
+        "AUDIENCE STATISTICS \u{1F464}"
+    }
+
+    fn fields(&self) -> &'static [&'static str] {
+        &["audience"]
+    }
+
+    fn render(&self, statistics: &Statistics, out: &mut dyn Write) -> fmt::Result {
+        let audience = &statistics.audience;
+        if audience.likes_received > 0 && audience.videos_published == 0 {
+            writeln!(
+                out,
+                "You've received {} likes but no videos were found in the data. You must have deleted old videos",
+                audience.likes_received
+            )?;
+        } else if audience.likes_received == 0 && audience.videos_published == 0 {
+            writeln!(out, "You haven't posted any videos or received any likes")?;
+        } else if audience.likes_received == 0 && audience.videos_published > 0 {
+            writeln!(
+                out,
+                "You've published {} videos - sadly no one liked them",
+                audience.videos_published
+            )?;
+        } else {
+            writeln!(
+                out,
+                "You've received {} likes with {} videos",
+                audience.likes_received, audience.videos_published
+            )?;
+            writeln!(
+                out,
+                "You got {} likes per video on average",
+                (audience.likes_received / audience.videos_published)
+            )?;
+        }
+        writeln!(out, "Note that likes from old videos are still counted.")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+// shares.rs
+// shares.rs is in charge of :
+// - reading the share history of the "Your Activity" section
+// - breaking shares down by month, by share channel (method) and by shared content type
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{count_per_month, date_field, date_info_by_timestamp, value_length, ActivityItem, DateInfo, UserTimeZone};
+
+#[derive(Serialize)]
+pub struct ShareStats {
+    pub total: usize,
+    pub per_month: BTreeMap<String, usize>,
+    pub by_method: HashMap<String, usize>,
+    pub by_content: HashMap<String, usize>,
+    pub share_info: Option<DateInfo>,
+    pub shares_per_watched_video: f64,
+}
+
+pub fn share_stats(data: &Value, tz: &UserTimeZone) -> ShareStats {
+    let share_history = &data["Your Activity"]["Share History"]["ShareHistoryList"];
+    let entries = share_history.as_array().map(|a| a.as_slice()).unwrap_or(&[]);
+
+    let mut by_method = HashMap::new();
+    let mut by_content = HashMap::new();
+    let mut items = Vec::new();
+
+    for entry in entries {
+        let method = entry.get("Method").and_then(|m| m.as_str()).unwrap_or("");
+        *by_method.entry(channel_name(method)).or_insert(0) += 1;
+
+        let content = entry.get("SharedContent").and_then(|c| c.as_str()).unwrap_or("");
+        let content = if content.is_empty() { "unknown" } else { content };
+        *by_content.entry(content.to_lowercase()).or_insert(0) += 1;
+
+        items.push(ActivityItem::new(
+            date_field(entry, &["Date"]).unwrap_or_default(),
+            entry.get("Link").and_then(|l| l.as_str()).unwrap_or("No link found"),
+        ));
+    }
+
+    let watched_videos_len = value_length(&data["Your Activity"]["Watch History"]["VideoList"]);
+    let shares_per_watched_video = if watched_videos_len > 0 {
+        entries.len() as f64 / watched_videos_len as f64
+    } else {
+        0.0
+    };
+
+    ShareStats {
+        total: entries.len(),
+        per_month: count_per_month(&items, tz),
+        by_method,
+        by_content,
+        share_info: date_info_by_timestamp(&items),
+        shares_per_watched_video,
+    }
+}
+
+// TikTok stores the raw method identifier (e.g. "chat_head", "copy"), so we give the common ones readable names
+fn channel_name(method: &str) -> String {
+    match method.trim().to_lowercase().as_str() {
+        "" => String::from("Unknown"),
+        "copy" | "copy link" | "copylink" | "copy_link" => String::from("Copy link"),
+        "whatsapp" | "whatsapp_status" => String::from("WhatsApp"),
+        "instagram" | "instagram_direct" | "instagram_story" => String::from("Instagram"),
+        "chat_head" | "dm" | "direct message" | "message" | "im" => String::from("Direct message"),
+        "facebook" | "facebook_messenger" | "messenger" => String::from("Facebook"),
+        "sms" => String::from("SMS"),
+        "telegram" => String::from("Telegram"),
+        "snapchat" => String::from("Snapchat"),
+        "twitter" | "x" => String::from("X (Twitter)"),
+        "email" => String::from("Email"),
+        "download" | "save" | "save_video" => String::from("Download"),
+        other => other.to_string(),
+    }
+}
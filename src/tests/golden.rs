@@ -0,0 +1,105 @@
+// golden.rs
+// golden.rs is in charge of :
+// - running a synthetic export (made with `generate --seed 1 --days 21 --users 8 --start 2024-03-01`) through Statistics::build
+// - comparing every output format to the expected files checked in next to it, so that any change in a count,
+// a rate, a first/last item or a chart shows up in the diff of the expected files
+// After an intended change, run `UPDATE_GOLDEN=1 cargo test` to rewrite the expected files, and review their diff
+
+use std::fs;
+use std::path::Path;
+
+use super::{options, tests_read_file};
+use crate::{
+    all_sections, flatten_json, render_report, report_json, select_sections, to_csv, AnalysisWindow, Statistics,
+};
+
+const GOLDEN_DIR: &str = "src/tests/golden";
+
+fn statistics() -> Statistics {
+    let file = tests_read_file::file_into_str(&format!("{}/user_data.json", GOLDEN_DIR));
+    let data = tests_read_file::str_into_object(file).expect("the golden export is valid JSON");
+    Statistics::build_with(data, &options(AnalysisWindow::default()))
+}
+
+fn check_golden(name: &str, actual: &str) {
+    let path = Path::new(GOLDEN_DIR).join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap_or_else(|err| panic!("could not write {}: {}", path.display(), err));
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("could not read {} ({}), run UPDATE_GOLDEN=1 cargo test", path.display(), err));
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "{} differs from the output at line {}:\n  expected: {:?}\n  actual:   {:?}\nRun UPDATE_GOLDEN=1 cargo test if the change is intended",
+            name,
+            line + 1,
+            expected.lines().nth(line).unwrap_or("(end of file)"),
+            actual.lines().nth(line).unwrap_or("(end of output)")
+        );
+    }
+}
+
+#[test]
+fn golden_text_report() {
+    check_golden("report.txt", &render_report(&statistics(), &all_sections(), false));
+}
+
+// With every section the JSON report is the statistics export, so this one pins the fields a section
+// selection keeps: the header and the chosen sections only
+#[test]
+fn golden_json_report() {
+    let names: Vec<String> = ["dms", "logins", "audience"].iter().map(|name| name.to_string()).collect();
+    let report = report_json(&statistics(), &select_sections(&names).unwrap());
+    check_golden("report.json", &(serde_json::to_string_pretty(&report).unwrap() + "\n"));
+}
+
+#[test]
+fn golden_statistics_export() {
+    // Going through a Value sorts the keys of the HashMap fields, so the file is the same on every run
+    let value = serde_json::to_value(statistics()).unwrap();
+    check_golden("statistics.json", &(serde_json::to_string_pretty(&value).unwrap() + "\n"));
+
+    let rows: Vec<Vec<String>> = flatten_json(&value)
+        .into_iter()
+        .map(|(field, value)| vec![field, value])
+        .collect();
+    check_golden("statistics.csv", &to_csv(&["field", "value"], &rows));
+}
+
+#[test]
+fn golden_redacted_report() {
+    let mut statistics = statistics();
+    statistics.redact();
+    check_golden("report_redacted.txt", &render_report(&statistics, &all_sections(), false));
+}
+
+// The expected files are only worth something if the statistics they hold are right,
+// these are counted directly from the fixture
+#[test]
+fn golden_export_matches_its_contents() {
+    let file = tests_read_file::file_into_str(&format!("{}/user_data.json", GOLDEN_DIR));
+    let data = tests_read_file::str_into_object(file).unwrap();
+    let statistics = statistics();
+    let length = |value: &serde_json::Value| value.as_array().map(|list| list.len()).unwrap_or(0);
+
+    assert_eq!(statistics.username, "demo.user");
+    assert_eq!(statistics.logins.openings, length(&data["Your Activity"]["Login History"]["LoginHistoryList"]));
+    assert_eq!(statistics.watched.videos_watched, length(&data["Your Activity"]["Watch History"]["VideoList"]));
+    assert_eq!(statistics.likes_left.videos_liked, length(&data["Your Activity"]["Like List"]["ItemFavoriteList"]));
+    assert_eq!(statistics.comments, length(&data["Comment"]["Comments"]["CommentsList"]));
+    let chats = data["Direct Message"]["Direct Messages"]["ChatHistory"].as_object().unwrap();
+    assert_eq!(statistics.dms.values().sum::<usize>(), chats.values().map(length).sum::<usize>());
+
+    let watched = &data["Your Activity"]["Watch History"]["VideoList"];
+    let watch_info = statistics.watch_info.as_ref().unwrap();
+    assert_eq!(watch_info.last.content, watched[0]["Link"].as_str().unwrap());
+    assert_eq!(watch_info.first.raw_date, watched[length(watched) - 1]["Date"].as_str().unwrap());
+    assert!(statistics.unparsed_dates.is_empty());
+}
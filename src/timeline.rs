@@ -0,0 +1,68 @@
+// timeline.rs
+// timeline.rs is in charge of :
+// - counting the dated records of every section of the export, day by day or month by month
+// - giving a single view of when the account was active, whatever the kind of activity
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::{date_utils, record_date, UserTimeZone};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimelineStep {
+    Day,
+    Month,
+}
+
+#[derive(Serialize)]
+pub struct TimelinePeriod {
+    pub period: String,
+    pub total: usize,
+    // Subsection name ("Watch History", "Like List"...) -> number of records
+    pub sections: BTreeMap<String, usize>,
+}
+
+// Records are counted under their subsection, the second level of the export.
+// Periods without any activity are left out
+pub fn build_timeline(data: &Value, tz: &UserTimeZone, step: TimelineStep) -> Vec<TimelinePeriod> {
+    let mut periods: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut stack: Vec<(Vec<&str>, &Value)> = vec![(Vec::new(), data)];
+
+    while let Some((path, value)) = stack.pop() {
+        let timestamp = record_date(value).and_then(|date| date_utils::date_to_unix_timestamp(&date));
+        if let Some(timestamp) = timestamp {
+            let local = tz.local_date_time(timestamp);
+            let period = match step {
+                TimelineStep::Day => local.format("%Y-%m-%d").to_string(),
+                TimelineStep::Month => local.format("%Y-%m").to_string(),
+            };
+            let section = path.get(1).or(path.first()).copied().unwrap_or("Export");
+            *periods.entry(period).or_default().entry(section.to_string()).or_insert(0) += 1;
+            // A record is counted once, even if it holds other dated values
+            continue;
+        }
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let mut child_path = path.clone();
+                    if child_path.len() < 2 {
+                        child_path.push(key.as_str());
+                    }
+                    stack.push((child_path, child));
+                }
+            }
+            Value::Array(list) => stack.extend(list.iter().map(|child| (path.clone(), child))),
+            _ => {}
+        }
+    }
+
+    periods
+        .into_iter()
+        .map(|(period, sections)| TimelinePeriod {
+            period,
+            total: sections.values().sum(),
+            sections,
+        })
+        .collect()
+}
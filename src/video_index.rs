@@ -0,0 +1,205 @@
+// video_index.rs
+// video_index.rs is in charge of :
+// - extracting the numeric video ID from TikTok links
+// - joining the watch history, likes, favorite videos and shares on that ID
+// - turning the joined lists into an engagement funnel (watched -> liked -> favorited -> shared)
+// - finding the videos that were watched more than once
+
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::{date_field, date_utils, UserTimeZone};
+
+#[derive(Default)]
+pub struct VideoRecord {
+    pub link: String,
+    pub watch_dates: Vec<String>,
+    pub liked: bool,
+    pub favorited: bool,
+    pub shares: usize,
+}
+
+pub struct VideoIndex {
+    pub videos: HashMap<String, VideoRecord>,
+}
+
+#[derive(Serialize)]
+pub struct EngagementFunnel {
+    pub watched: usize,
+    pub liked: usize,
+    pub favorited: usize,
+    pub shared: usize,
+    pub liked_percentage: f64,
+    pub favorited_percentage: f64,
+    pub shared_percentage: f64,
+}
+
+#[derive(Serialize)]
+pub struct RewatchedVideo {
+    pub video_id: String,
+    pub link: String,
+    pub views: usize,
+    pub first_watched: String,
+    pub last_watched: String,
+    pub span_days: usize,
+}
+
+#[derive(Serialize)]
+pub struct RewatchStats {
+    pub rewatched_videos: usize,
+    pub extra_views: usize,
+    pub top: Vec<RewatchedVideo>,
+}
+
+// Links look like ".../share/video/7212345678901234567/" or ".../@user/video/7212345678901234567?lang=en".
+// Short links (vm.tiktok.com/...) carry no ID and are ignored
+pub fn video_id(link: &str) -> Option<String> {
+    let mut segments = link.split(['/', '?', '#']);
+    if segments.any(|segment| segment == "video") {
+        if let Some(id) = segments.next().filter(|id| is_video_id(id)) {
+            return Some(id.to_string());
+        }
+    }
+
+    link.split(|c: char| !c.is_ascii_digit())
+        .find(|run| is_video_id(run))
+        .map(|id| id.to_string())
+}
+
+fn is_video_id(candidate: &str) -> bool {
+    candidate.len() >= 15 && candidate.chars().all(|c| c.is_ascii_digit())
+}
+
+impl VideoIndex {
+    pub fn build(data: &Value) -> VideoIndex {
+        let mut index = VideoIndex {
+            videos: HashMap::new(),
+        };
+        let activity = &data["Your Activity"];
+
+        for (link, date) in links(&activity["Watch History"]["VideoList"], "Link", "Date") {
+            index.record(link).watch_dates.push(date.to_string());
+        }
+        for (link, _) in links(&activity["Like List"]["ItemFavoriteList"], "link", "date") {
+            index.record(link).liked = true;
+        }
+        for (link, _) in links(&activity["Favorite Videos"]["FavoriteVideoList"], "Link", "Date") {
+            index.record(link).favorited = true;
+        }
+        for (link, _) in links(&activity["Share History"]["ShareHistoryList"], "Link", "Date") {
+            index.record(link).shares += 1;
+        }
+
+        index.videos.remove("");
+        index
+    }
+
+    pub fn funnel(&self) -> EngagementFunnel {
+        let watched: Vec<&VideoRecord> = self
+            .videos
+            .values()
+            .filter(|video| !video.watch_dates.is_empty())
+            .collect();
+        let liked = watched.iter().filter(|video| video.liked).count();
+        let favorited = watched.iter().filter(|video| video.favorited).count();
+        let shared = watched.iter().filter(|video| video.shares > 0).count();
+
+        let percentage = |count: usize| {
+            if watched.is_empty() {
+                0.0
+            } else {
+                count as f64 / watched.len() as f64 * 100.0
+            }
+        };
+
+        EngagementFunnel {
+            watched: watched.len(),
+            liked,
+            favorited,
+            shared,
+            liked_percentage: percentage(liked),
+            favorited_percentage: percentage(favorited),
+            shared_percentage: percentage(shared),
+        }
+    }
+
+    // Videos seen more than once, most viewed first. Only the `top` first ones are detailed
+    pub fn rewatches(&self, top: usize, tz: &UserTimeZone) -> RewatchStats {
+        let mut rewatched: Vec<RewatchedVideo> = self
+            .videos
+            .iter()
+            .filter(|(_, video)| video.watch_dates.len() > 1)
+            .map(|(id, video)| rewatched_video(id, video, tz))
+            .collect();
+        rewatched.sort_by(|a, b| {
+            b.views
+                .cmp(&a.views)
+                .then(b.span_days.cmp(&a.span_days))
+                .then(a.video_id.cmp(&b.video_id))
+        });
+
+        let rewatched_videos = rewatched.len();
+        let extra_views = rewatched.iter().map(|video| video.views - 1).sum();
+        rewatched.truncate(top);
+
+        RewatchStats {
+            rewatched_videos,
+            extra_views,
+            top: rewatched,
+        }
+    }
+
+    // Links without a video ID all land on the "" entry, which is dropped once the index is built
+    fn record(&mut self, link: &str) -> &mut VideoRecord {
+        let id = video_id(link).unwrap_or_default();
+        let record = self.videos.entry(id).or_default();
+        if record.link.is_empty() {
+            record.link = link.to_string();
+        }
+        record
+    }
+}
+
+fn rewatched_video(id: &str, video: &VideoRecord, tz: &UserTimeZone) -> RewatchedVideo {
+    let mut dated: Vec<(i64, &String)> = video
+        .watch_dates
+        .iter()
+        .filter_map(|date| date_utils::date_to_unix_timestamp(date).map(|ts| (ts, date)))
+        .collect();
+    dated.sort();
+
+    let (first_watched, last_watched, span_days) = match (dated.first(), dated.last()) {
+        (Some((_, first)), Some((last_ts, last))) => (
+            first.to_string(),
+            last.to_string(),
+            date_utils::days_between(*last_ts, first, tz).unwrap_or(0),
+        ),
+        _ => (String::new(), String::new(), 0),
+    };
+
+    RewatchedVideo {
+        video_id: id.to_string(),
+        link: video.link.clone(),
+        views: video.watch_dates.len(),
+        first_watched,
+        last_watched,
+        span_days,
+    }
+}
+
+fn links<'a>(list: &'a Value, link_key: &str, date_key: &str) -> Vec<(&'a str, Cow<'a, str>)> {
+    list.as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let link = entry.get(link_key).and_then(|l| l.as_str())?;
+                    let date = date_field(entry, &[date_key]).unwrap_or_default();
+                    Some((link, date))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
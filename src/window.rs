@@ -0,0 +1,153 @@
+// window.rs
+// window.rs is in charge of :
+// - turning the --since and --until options into an AnalysisWindow (a start and an end timestamp)
+// - removing every dated record outside of that window from the data, before the statistics are built
+
+use chrono::{DateTime, Duration, Months, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{date_utils, record_date, UserTimeZone};
+
+#[derive(Default, Clone, Serialize)]
+pub struct AnalysisWindow {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+}
+
+impl AnalysisWindow {
+    // `since` and `until` accept a year ("2024"), a month ("2024-03"), a day ("2024-03-15"),
+    // a full date ("2024-03-15 18:00:00") or a duration ("30d", "4w", "6m", "1y").
+    // Dates are read in the user's time zone.
+    // Durations are counted back from the latest activity of the export, not from today,
+    // so that "last 30 days" means the same thing whenever the export is analyzed
+    pub fn parse(
+        since: Option<&str>,
+        until: Option<&str>,
+        data: &Value,
+        tz: &UserTimeZone,
+    ) -> Result<AnalysisWindow, String> {
+        let latest = latest_activity(data);
+        let window = AnalysisWindow {
+            since: since.map(|s| parse_bound(s, latest, true, tz)).transpose()?,
+            until: until.map(|u| parse_bound(u, latest, false, tz)).transpose()?,
+        };
+
+        if let (Some(since), Some(until)) = (window.since, window.until) {
+            if since > until {
+                return Err(String::from("the start of the period is after its end"));
+            }
+        }
+        Ok(window)
+    }
+
+    pub fn is_everything(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.since.is_none_or(|since| timestamp >= since) && self.until.is_none_or(|until| timestamp <= until)
+    }
+
+    // Records without a (readable) date are kept, as there is no way to tell when they happened
+    pub fn apply(&self, data: &mut Value) {
+        if self.is_everything() {
+            return;
+        }
+        match data {
+            Value::Object(map) => {
+                map.retain(|_, child| self.keeps(child));
+                for child in map.values_mut() {
+                    self.apply(child);
+                }
+            }
+            Value::Array(list) => {
+                list.retain(|child| self.keeps(child));
+                for child in list.iter_mut() {
+                    self.apply(child);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn describe(&self, tz: &UserTimeZone) -> String {
+        let format = |ts: i64| tz.local_date_time(ts).format("%Y-%m-%d %H:%M:%S").to_string();
+        match (self.since, self.until) {
+            (None, None) => String::from("the whole history"),
+            (Some(since), None) => format!("everything since {}", format(since)),
+            (None, Some(until)) => format!("everything until {}", format(until)),
+            (Some(since), Some(until)) => format!("{} to {}", format(since), format(until)),
+        }
+    }
+
+    fn keeps(&self, record: &Value) -> bool {
+        record_date(record)
+            .and_then(|date| date_utils::date_to_unix_timestamp(&date))
+            .is_none_or(|ts| self.contains(ts))
+    }
+}
+
+pub(crate) fn latest_activity(data: &Value) -> i64 {
+    let mut latest = 0;
+    let mut stack = vec![data];
+    while let Some(value) = stack.pop() {
+        if let Some(ts) = record_date(value).and_then(|date| date_utils::date_to_unix_timestamp(&date)) {
+            latest = latest.max(ts);
+        }
+        match value {
+            Value::Object(map) => stack.extend(map.values()),
+            Value::Array(list) => stack.extend(list.iter()),
+            _ => {}
+        }
+    }
+    latest
+}
+
+// A bound covers a whole year, month or day: "--since 2024" starts on January 1st and
+// "--until 2024" ends on December 31st at 23:59:59
+fn parse_bound(bound: &str, latest: i64, is_start: bool, tz: &UserTimeZone) -> Result<i64, String> {
+    let bound = bound.trim();
+    let invalid = || format!("\"{}\" is not a valid date or duration", bound);
+
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(bound, "%Y-%m-%d %H:%M:%S") {
+        return tz.timestamp_of_local(date_time).ok_or_else(invalid);
+    }
+
+    let (start, end) = if let Ok(day) = NaiveDate::parse_from_str(bound, "%Y-%m-%d") {
+        (day, day)
+    } else if let Ok(first_day) = NaiveDate::parse_from_str(&format!("{}-01", bound), "%Y-%m-%d") {
+        let next_month = first_day.checked_add_months(Months::new(1)).ok_or_else(invalid)?;
+        (first_day, next_month.pred_opt().ok_or_else(invalid)?)
+    } else if let Ok(year) = bound.parse::<i32>() {
+        (
+            NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid)?,
+            NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(invalid)?,
+        )
+    } else {
+        return relative_bound(bound, latest).ok_or_else(invalid);
+    };
+
+    let time = if is_start {
+        start.and_hms_opt(0, 0, 0)
+    } else {
+        end.and_hms_opt(23, 59, 59)
+    };
+    time.and_then(|t| tz.timestamp_of_local(t)).ok_or_else(invalid)
+}
+
+fn relative_bound(bound: &str, latest: i64) -> Option<i64> {
+    let (split, unit) = bound.char_indices().last()?;
+    let amount = bound[..split].parse::<u32>().ok()?;
+    let latest_date = DateTime::from_timestamp(latest, 0)?;
+
+    // Durations reaching before the first representable date are rejected rather than panicking
+    let start = match unit {
+        'd' => latest_date.checked_sub_signed(Duration::days(amount as i64))?,
+        'w' => latest_date.checked_sub_signed(Duration::weeks(amount as i64))?,
+        'm' => latest_date.checked_sub_months(Months::new(amount))?,
+        'y' => latest_date.checked_sub_months(Months::new(amount.checked_mul(12)?))?,
+        _ => return None,
+    };
+    Some(start.timestamp())
+}